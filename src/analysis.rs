@@ -0,0 +1,657 @@
+//! Object-level analysis: splitting the board into separate objects and
+//! telling still lifes, oscillators and spaceships apart.
+
+use std::collections::VecDeque;
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::LifeError;
+use crate::rng::Rng;
+use crate::rule::Rule;
+use crate::{Boundary, Cell, Pattern, Universe};
+
+// Live cells at most this many neighbourhood radii apart, in rows and
+// columns, belong to the same object, since they can still reach each
+// other through a dead cell.
+const OBJECT_REACH: i64 = 2;
+
+// Longest period looked for when classifying an object.
+const MAX_OBJECT_PERIOD: u32 = 15;
+
+// Generations a random soup gets to settle in `Universe::random_still_life`,
+// and a board in `Universe::period_spectrum`.
+const STILL_LIFE_SETTLE_GENERATIONS: u32 = 1000;
+
+// Fewest live cells a settled soup needs to count as a still life worth
+// returning from `Universe::random_still_life`.
+const STILL_LIFE_MIN_POPULATION: u32 = 4;
+
+// Largest scratch board, in cells, `Universe::light_cone_radius` builds:
+// 1024 by 1024, enough for 510 generations at radius 1.
+const MAX_LIGHT_CONE_CELLS: u32 = 1 << 20;
+
+/// What an object does when it's left alone on an empty board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ObjectKind {
+    StillLife,
+    /// Comes back in place after the given number of generations.
+    Oscillator(u32),
+    /// Comes back somewhere else after the given number of generations.
+    Spaceship(u32),
+    /// Dies out, or doesn't come back within `MAX_OBJECT_PERIOD`.
+    Unknown,
+}
+
+impl Universe {
+    /// Split the live cells into separate objects, each cropped to its own
+    /// bounding box. Objects that straddle a wrapped edge come out whole.
+    pub(crate) fn objects(&self) -> Vec<Pattern> {
+        let (width, height) = (self.width as i64, self.height as i64);
+        let reach = OBJECT_REACH * i64::from(self.neighborhood_radius);
+        let mut seen = vec![false; self.cells.len()];
+        let mut objects = Vec::new();
+
+        for start in 0..self.cells.len() {
+            if seen[start] || self.cells[start] == Cell::Dead {
+                continue;
+            }
+            seen[start] = true;
+
+            // Positions are kept unwrapped, relative to the starting cell's
+            // row and column, so an object crossing an edge stays in one piece.
+            let origin = ((start as i64) / width, (start as i64) % width);
+            let mut stack = vec![origin];
+            let mut members = Vec::new();
+            while let Some((row, col)) = stack.pop() {
+                members.push((row, col));
+                for d_row in -reach..=reach {
+                    for d_col in -reach..=reach {
+                        let (r, c) = (row + d_row, col + d_col);
+                        let idx = (r.rem_euclid(height) * width + c.rem_euclid(width)) as usize;
+                        if !seen[idx] && self.cells[idx] == Cell::Alive {
+                            seen[idx] = true;
+                            stack.push((r, c));
+                        }
+                    }
+                }
+            }
+
+            objects.push(Pattern::from_positions(&members));
+        }
+
+        objects
+    }
+
+    /// Run `object` by itself under the current rule for up to
+    /// `MAX_OBJECT_PERIOD` generations and report how it repeats.
+    pub(crate) fn classify(&self, object: &Pattern) -> ObjectKind {
+        // Cells spread at most one neighbourhood radius per generation, so
+        // this margin keeps the object from meeting itself across the
+        // wrapped edges.
+        let margin = (MAX_OBJECT_PERIOD + 1) * self.neighborhood_radius;
+        let mut scratch = self.dead_with_rules(object.width + 2 * margin, object.height + 2 * margin);
+        scratch.insert_pattern(object, margin, margin, 0, false, false);
+
+        for period in 1..=MAX_OBJECT_PERIOD {
+            scratch.step();
+            match scratch.crop() {
+                Some(ref shape) if shape == object => {
+                    let moved = scratch.bounding_box().map(|(row, col, _, _)| (row, col))
+                        != Some((margin, margin));
+                    return match (moved, period) {
+                        (true, _) => ObjectKind::Spaceship(period),
+                        (false, 1) => ObjectKind::StillLife,
+                        (false, _) => ObjectKind::Oscillator(period),
+                    };
+                }
+                Some(_) => {}
+                None => return ObjectKind::Unknown,
+            }
+        }
+        ObjectKind::Unknown
+    }
+
+    /// Whether the board holds at least one object and every object is a
+    /// spaceship.
+    pub(crate) fn only_spaceships_left(&self) -> bool {
+        let objects = self.objects();
+        !objects.is_empty()
+            && objects
+                .iter()
+                .all(|object| matches!(self.classify(object), ObjectKind::Spaceship(_)))
+    }
+}
+
+impl Pattern {
+    /// A pattern holding live cells at the given positions, shifted so the
+    /// top-left corner of their bounding box is at `(0, 0)`.
+    pub(crate) fn from_positions(positions: &[(i64, i64)]) -> Pattern {
+        let min_row = positions.iter().map(|&(r, _)| r).min().unwrap_or(0);
+        let min_col = positions.iter().map(|&(_, c)| c).min().unwrap_or(0);
+        let max_row = positions.iter().map(|&(r, _)| r).max().unwrap_or(-1);
+        let max_col = positions.iter().map(|&(_, c)| c).max().unwrap_or(-1);
+
+        let width = (max_col - min_col + 1) as u32;
+        let height = (max_row - min_row + 1) as u32;
+        let mut cells = vec![Cell::Dead; (width * height) as usize];
+        for &(r, c) in positions {
+            cells[((r - min_row) as u32 * width + (c - min_col) as u32) as usize] = Cell::Alive;
+        }
+
+        Pattern { width, height, cells }
+    }
+}
+
+// Objects `Universe::census` knows by name, each in one of its phases as
+// `(name, width, row-major cells)`. Other phases and orientations are
+// matched through their canonical form.
+const CATALOG: [(&str, u32, &[u8]); 12] = [
+    ("block", 2, &[1, 1, 1, 1]),
+    ("beehive", 4, &[0, 1, 1, 0, 1, 0, 0, 1, 0, 1, 1, 0]),
+    ("loaf", 4, &[0, 1, 1, 0, 1, 0, 0, 1, 0, 1, 0, 1, 0, 0, 1, 0]),
+    ("boat", 3, &[1, 1, 0, 1, 0, 1, 0, 1, 0]),
+    ("ship", 3, &[1, 1, 0, 1, 0, 1, 0, 1, 1]),
+    ("tub", 3, &[0, 1, 0, 1, 0, 1, 0, 1, 0]),
+    ("pond", 4, &[0, 1, 1, 0, 1, 0, 0, 1, 1, 0, 0, 1, 0, 1, 1, 0]),
+    ("blinker", 3, &[1, 1, 1]),
+    ("toad", 4, &[0, 1, 1, 1, 1, 1, 1, 0]),
+    ("beacon", 4, &[1, 1, 0, 0, 1, 1, 0, 0, 0, 0, 1, 1, 0, 0, 1, 1]),
+    ("glider", 3, &[0, 1, 0, 0, 0, 1, 1, 1, 1]),
+    ("lwss", 5, &[0, 1, 0, 0, 1, 1, 0, 0, 0, 0, 1, 0, 0, 0, 1, 1, 1, 1, 1, 0]),
+];
+
+impl Universe {
+    /// Run the board until it settles and count what's left by object type.
+    ///
+    /// The board advances until every object on it is a still life,
+    /// oscillator or spaceship, or until `max_gen` generations have passed.
+    /// Each object is then named from a small catalog of common ones
+    /// (`block`, `beehive`, `blinker`, `glider`, ...) regardless of phase or
+    /// orientation; anything else is counted as `unknown`. Counts come
+    /// back largest first, ties by name. Note that this advances this board.
+    pub fn census(&mut self, max_gen: u32) -> Vec<(String, u32)> {
+        let objects = self.settle_objects(max_gen);
+
+        let catalog: Vec<(&str, Pattern)> = CATALOG
+            .iter()
+            .map(|&(name, width, cells)| {
+                let entry = Pattern::new(width, cells);
+                let kind = self.classify(&entry);
+                (name, self.canonical(&entry, kind))
+            })
+            .collect();
+
+        let mut counts: Vec<(String, u32)> = Vec::new();
+        for (object, kind) in objects {
+            let name = match kind {
+                ObjectKind::Unknown => "unknown",
+                _ => {
+                    let canonical = self.canonical(&object, kind);
+                    catalog
+                        .iter()
+                        .find(|(_, entry)| *entry == canonical)
+                        .map_or("unknown", |&(name, _)| name)
+                }
+            };
+            match counts.iter_mut().find(|(counted, _)| counted == name) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((name.to_string(), 1)),
+            }
+        }
+
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+
+    /// How many oscillators of each period are left once the board has
+    /// settled, as `(period, count)` pairs by increasing period. Still
+    /// lifes and spaceships aren't oscillators and are left out, as is
+    /// anything still unclassified after settling.
+    ///
+    /// A copy of the board runs until every object on it is a still
+    /// life, oscillator or spaceship, for at most 1000 generations; this
+    /// board is left as it is.
+    pub fn period_spectrum(&mut self) -> Vec<(u32, u32)> {
+        let mut scratch = self.scratch();
+        let mut spectrum: Vec<(u32, u32)> = Vec::new();
+        for (_, kind) in scratch.settle_objects(STILL_LIFE_SETTLE_GENERATIONS) {
+            if let ObjectKind::Oscillator(period) = kind {
+                match spectrum.iter_mut().find(|(counted, _)| *counted == period) {
+                    Some((_, count)) => *count += 1,
+                    None => spectrum.push((period, 1)),
+                }
+            }
+        }
+        spectrum.sort_unstable();
+        spectrum
+    }
+
+    /// Tick until every object is classified, or for `max_gen` generations,
+    /// and return the objects as they are then.
+    fn settle_objects(&mut self, max_gen: u32) -> Vec<(Pattern, ObjectKind)> {
+        let mut objects = self.classified_objects();
+        for _ in 0..max_gen {
+            if objects.iter().all(|&(_, kind)| kind != ObjectKind::Unknown) {
+                break;
+            }
+            self.step();
+            objects = self.classified_objects();
+        }
+        objects
+    }
+
+    /// Every object on the board along with its classification.
+    fn classified_objects(&self) -> Vec<(Pattern, ObjectKind)> {
+        self.objects()
+            .into_iter()
+            .map(|object| {
+                let kind = self.classify(&object);
+                (object, kind)
+            })
+            .collect()
+    }
+
+    /// One representative shared by every phase and orientation of
+    /// `object`, which repeats as `kind` says.
+    fn canonical(&self, object: &Pattern, kind: ObjectKind) -> Pattern {
+        let period = match kind {
+            ObjectKind::Oscillator(period) | ObjectKind::Spaceship(period) => period,
+            ObjectKind::StillLife | ObjectKind::Unknown => 1,
+        };
+
+        let margin = period * self.neighborhood_radius;
+        let mut scratch = self.dead_with_rules(object.width + 2 * margin, object.height + 2 * margin);
+        scratch.insert_pattern(object, margin, margin, 0, false, false);
+
+        let mut best: Option<Pattern> = None;
+        for _ in 0..period {
+            if let Some(phase) = scratch.crop() {
+                for &flip_h in &[false, true] {
+                    for rotation in 0..4 {
+                        let candidate = phase.transformed(rotation, flip_h, false);
+                        if best.as_ref().is_none_or(|best| candidate.sort_key() < best.sort_key()) {
+                            best = Some(candidate);
+                        }
+                    }
+                }
+            }
+            scratch.step();
+        }
+        best.unwrap_or_else(|| object.clone())
+    }
+}
+
+impl Pattern {
+    /// A total order so a smallest transform can be picked.
+    fn sort_key(&self) -> (u32, u32, Vec<u8>) {
+        (self.width, self.height, self.cells.iter().map(|&cell| cell as u8).collect())
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// A `width` x `height` board holding a still life grown from a random
+    /// soup, or `None` if none turned up within `max_attempts` soups.
+    ///
+    /// Each attempt fills the board at random, from a generator seeded
+    /// with `seed` so the same arguments give the same board, and runs it
+    /// for up to 1000 generations. Soups that settle into anything that
+    /// still changes (oscillators, or spaceships that never settle), die
+    /// out or leave fewer than 4 live cells are thrown away.
+    pub fn random_still_life(width: u32, height: u32, seed: u64, max_attempts: u32) -> Option<Universe> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let mut rng = Rng::new(seed);
+        for _ in 0..max_attempts {
+            let mut soup = Universe::dead(width, height);
+            for cell in soup.cells.iter_mut() {
+                if rng.next_f64() < 0.5 {
+                    *cell = Cell::Alive;
+                }
+            }
+
+            if soup.settle(STILL_LIFE_SETTLE_GENERATIONS) == Some(1)
+                && soup.live_count() >= STILL_LIFE_MIN_POPULATION
+            {
+                let mut still_life = Universe::dead(width, height);
+                still_life.cells = soup.cells;
+                return Some(still_life);
+            }
+        }
+        None
+    }
+}
+
+impl Universe {
+    /// Tick until the whole board repeats itself, which must happen within
+    /// `MAX_OBJECT_PERIOD` generations of a repeat to be noticed, and return
+    /// the period it repeats with, 1 for a still board. Gives up with `None`
+    /// after `max_gen` generations.
+    pub(crate) fn settle(&mut self, max_gen: u32) -> Option<u32> {
+        let mut recent = VecDeque::with_capacity(MAX_OBJECT_PERIOD as usize);
+        recent.push_front(self.state_hash());
+        for _ in 0..max_gen {
+            self.step();
+            let hash = self.state_hash();
+            if let Some(period) = recent.iter().position(|&seen| seen == hash) {
+                return Some(period as u32 + 1);
+            }
+            if recent.len() == MAX_OBJECT_PERIOD as usize {
+                recent.pop_back();
+            }
+            recent.push_front(hash);
+        }
+        None
+    }
+}
+
+impl Universe {
+    /// Find where the board's evolution starts repeating, using Floyd's
+    /// tortoise-and-hare search over two copies of the board, so memory
+    /// stays constant however long the cycle is.
+    ///
+    /// Returns `(mu, lambda)`: the board first reaches a state it will
+    /// return to after `mu` generations, and from then on repeats every
+    /// `lambda` generations (1 for a board that stops changing). `None`
+    /// means no repeat was found with the hare at most `max_gen`
+    /// generations ahead. The search runs on copies; this board is left
+    /// as it was.
+    ///
+    /// The hare needs 2 generations for a step, so a `max_gen` of 1 only
+    /// finds a board that is already still, as `(0, 1)`.
+    pub fn detect_cycle_floyd(&mut self, max_gen: u32) -> Option<(u32, u32)> {
+        let same = |a: &Universe, b: &Universe| a.state_hash() == b.state_hash() && a.cells == b.cells;
+
+        if max_gen == 1 {
+            let mut next = self.scratch();
+            next.step();
+            return if same(self, &next) { Some((0, 1)) } else { None };
+        }
+
+        // Phase 1: the hare runs twice as fast until it laps the tortoise
+        // somewhere inside the cycle.
+        let mut tortoise = self.scratch();
+        let mut hare = self.scratch();
+        let mut hare_generations = 0;
+        loop {
+            if hare_generations + 2 > max_gen {
+                return None;
+            }
+            tortoise.step();
+            hare.step();
+            hare.step();
+            hare_generations += 2;
+            if same(&tortoise, &hare) {
+                break;
+            }
+        }
+
+        // Phase 2: restarted from the beginning, the tortoise meets the hare
+        // at the first repeated state after mu generations.
+        let mut tortoise = self.scratch();
+        let mut mu = 0;
+        while !same(&tortoise, &hare) {
+            tortoise.step();
+            hare.step();
+            mu += 1;
+        }
+
+        // Phase 3: walk once around the cycle to measure its length.
+        let mut hare = tortoise.clone();
+        let mut lambda = 0;
+        loop {
+            hare.step();
+            lambda += 1;
+            if same(&tortoise, &hare) {
+                break;
+            }
+        }
+
+        Some((mu, lambda))
+    }
+
+    /// Splits a period-`period` oscillator into its stator, the cells alive
+    /// in every phase, and its rotor, the cells alive in some phases but
+    /// not others. Returns `(stator, rotor)` as flat indices in ascending
+    /// order; cells dead throughout are in neither.
+    ///
+    /// This takes the board to be a clean oscillator of exactly that
+    /// period and doesn't check: it just runs `period` generations on a
+    /// copy and compares them, so anything else on the board, or a wrong
+    /// period, gives a meaningless split. This board is left as it was.
+    pub fn stator_rotor(&mut self, period: u32) -> (Vec<u32>, Vec<u32>) {
+        let mut always_alive: Vec<bool> = self.cells.iter().map(|&cell| cell == Cell::Alive).collect();
+        let mut ever_alive = always_alive.clone();
+
+        let mut scratch = self.scratch();
+        for _ in 0..period {
+            scratch.step();
+            for (idx, &cell) in scratch.cells.iter().enumerate() {
+                let alive = cell == Cell::Alive;
+                always_alive[idx] &= alive;
+                ever_alive[idx] |= alive;
+            }
+        }
+
+        let mut stator = Vec::new();
+        let mut rotor = Vec::new();
+        for (idx, (&always, &ever)) in always_alive.iter().zip(&ever_alive).enumerate() {
+            if always {
+                stator.push(idx as u32);
+            } else if ever {
+                rotor.push(idx as u32);
+            }
+        }
+        (stator, rotor)
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// How far, in cells, anything spread from a single live cell within
+    /// `generations` ticks: a demonstration that nothing in Life travels
+    /// faster than one cell per generation, its "speed of light".
+    ///
+    /// The setup is fixed: one live cell in the middle of an otherwise
+    /// empty board with a `Dead` boundary, large enough that nothing can
+    /// reach its edges, running this board's rule and neighbourhood. The
+    /// result is the furthest any live cell ever got from the start,
+    /// counting diagonal steps as one, and so is never more than
+    /// `generations` times the neighbourhood radius. Under Conway's rule
+    /// the lone cell just dies, giving 0; a rule such as `B1/S` fills the
+    /// whole cone. The run happens on a scratch board; this one is left as
+    /// it is.
+    ///
+    /// A rule with `B0` has no cone to measure, since every dead cell is
+    /// born at once however far it is from the start, and is refused, as
+    /// is a `generations` that would need a scratch board of more than
+    /// about a million cells (past 510 generations at radius 1, or 255 at
+    /// radius 2).
+    pub fn light_cone_radius(&mut self, generations: u32) -> Result<u32, LifeError> {
+        if self.scheduled_rule().births_on_zero() {
+            return Err(LifeError::Unsupported("a rule with B0 brings the whole empty board to life"));
+        }
+        let too_large = || LifeError::ArgumentTooLarge("generations");
+        let reach = generations.checked_mul(self.neighborhood_radius).ok_or_else(too_large)?;
+        let origin = reach.checked_add(1).ok_or_else(too_large)?;
+        let side = origin
+            .checked_mul(2)
+            .and_then(|twice| twice.checked_add(1))
+            .filter(|side| side.checked_mul(*side).is_some_and(|cells| cells <= MAX_LIGHT_CONE_CELLS))
+            .ok_or_else(too_large)?;
+
+        let mut scratch = self.dead_with_rules(side, side);
+        scratch.boundary = Boundary::Dead;
+        scratch.set_cells(&[(origin, origin)]);
+
+        let mut radius = 0;
+        for _ in 0..generations {
+            scratch.step();
+            match scratch.bounding_box() {
+                Some((min_row, min_col, max_row, max_col)) => {
+                    let furthest = (origin - min_row).max(max_row - origin).max(origin - min_col).max(max_col - origin);
+                    radius = radius.max(furthest);
+                }
+                // An empty board stays empty.
+                None => break,
+            }
+        }
+        Ok(radius)
+    }
+}
+
+// Largest board, in cells, that predecessor searches will take on.
+const MAX_PREDECESSOR_SEARCH_CELLS: u32 = 64;
+
+/// How a bounded predecessor search ended.
+enum PredecessorSearch {
+    Found(Vec<Cell>),
+    /// Every assignment was tried: the board has no predecessor.
+    NoneExist,
+    /// The node budget ran out first.
+    GaveUp,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// A board that turns into this one after a single `tick`, found by
+    /// backtracking over the cells of a candidate predecessor, or `None`.
+    ///
+    /// The search tries at most `max_nodes` partial boards, so `None` means
+    /// either that no predecessor exists or that the budget ran out first.
+    /// Only boards of up to 64 cells are searched, and only with a
+    /// `Toroidal` boundary and the radius 1 neighbourhood; anything else
+    /// gives `None`.
+    pub fn find_predecessor(&self, max_nodes: u32) -> Option<Universe> {
+        match self.search_predecessor(max_nodes) {
+            PredecessorSearch::Found(cells) => {
+                let mut predecessor = self.dead_with_rules(self.width, self.height);
+                predecessor.cells = cells;
+                Some(predecessor)
+            }
+            PredecessorSearch::NoneExist | PredecessorSearch::GaveUp => None,
+        }
+    }
+
+    /// Whether no predecessor turned up within `search_depth` search nodes,
+    /// making the board a candidate Garden of Eden: a state no board can
+    /// evolve into. This is a heuristic rather than a proof, since a bigger
+    /// budget may still find one, and it only speaks for this torus. Boards
+    /// over 64 cells are too large to search and always return `false`, as
+    /// do boards `find_predecessor` can't search for other reasons.
+    pub fn is_likely_garden_of_eden(&self, search_depth: u32) -> bool {
+        if !self.can_search_predecessors() {
+            return false;
+        }
+        !matches!(self.search_predecessor(search_depth), PredecessorSearch::Found(_))
+    }
+}
+
+impl Universe {
+    /// Whether the board is small enough for `search_predecessor` and uses
+    /// the wrapped radius 1 neighbourhood, the only one it models.
+    fn can_search_predecessors(&self) -> bool {
+        let cell_count = self.width * self.height;
+        cell_count > 0
+            && cell_count <= MAX_PREDECESSOR_SEARCH_CELLS
+            && self.neighborhood_radius == 1
+            && self.scheduled_boundary() == Boundary::Toroidal
+    }
+
+    fn search_predecessor(&self, max_nodes: u32) -> PredecessorSearch {
+        if !self.can_search_predecessors() {
+            return PredecessorSearch::GaveUp;
+        }
+        let cell_count = self.width * self.height;
+
+        // Cells are assigned in row-major order; each target cell can be
+        // checked as soon as the last cell of its 3x3 block is assigned.
+        let (width, height) = (self.width as i64, self.height as i64);
+        let block = |idx: usize| -> Vec<usize> {
+            let (row, col) = (idx as i64 / width, idx as i64 % width);
+            let mut block: Vec<usize> = (-1..=1)
+                .flat_map(|d_row| (-1..=1).map(move |d_col| (d_row, d_col)))
+                .map(|(d_row, d_col)| {
+                    ((row + d_row).rem_euclid(height) * width + (col + d_col).rem_euclid(width)) as usize
+                })
+                .collect();
+            block.sort_unstable();
+            block.dedup();
+            block
+        };
+        let blocks: Vec<Vec<usize>> = (0..cell_count as usize).map(block).collect();
+        let mut checks = vec![Vec::new(); cell_count as usize];
+        for (target, block) in blocks.iter().enumerate() {
+            checks[*block.last().unwrap()].push(target);
+        }
+
+        let mut search = Backtrack {
+            target: &self.cells,
+            rule: self.scheduled_rule(),
+            weights: self.neighbor_weights,
+            width: self.width,
+            height: self.height,
+            checks,
+            assigned: vec![Cell::Dead; cell_count as usize],
+            nodes_left: max_nodes,
+        };
+        match search.assign(0) {
+            Some(true) => PredecessorSearch::Found(search.assigned),
+            Some(false) => PredecessorSearch::NoneExist,
+            None => PredecessorSearch::GaveUp,
+        }
+    }
+}
+
+struct Backtrack<'a> {
+    target: &'a [Cell],
+    rule: Rule,
+    weights: [i32; 8],
+    width: u32,
+    height: u32,
+    checks: Vec<Vec<usize>>,
+    assigned: Vec<Cell>,
+    nodes_left: u32,
+}
+
+impl<'a> Backtrack<'a> {
+    /// Try both states for cell `idx` and everything after it. `Some(true)`
+    /// when a full predecessor was found, `Some(false)` when none exists
+    /// below this point, `None` when the node budget ran out.
+    fn assign(&mut self, idx: usize) -> Option<bool> {
+        if idx == self.assigned.len() {
+            return Some(true);
+        }
+        for &state in &[Cell::Dead, Cell::Alive] {
+            if self.nodes_left == 0 {
+                return None;
+            }
+            self.nodes_left -= 1;
+
+            self.assigned[idx] = state;
+            if self.checks[idx].iter().all(|&target| self.produces(target)) && self.assign(idx + 1)? {
+                return Some(true);
+            }
+        }
+        Some(false)
+    }
+
+    /// Whether the assigned cells around `target` evolve into its state.
+    fn produces(&self, target: usize) -> bool {
+        let (width, height) = (self.width as i64, self.height as i64);
+        let (row, col) = (target as i64 / width, target as i64 % width);
+        // Reading order matches the NW, N, NE, W, E, SW, S, SE weights.
+        let neighbors = (-1..=1)
+            .flat_map(|d_row| (-1..=1).map(move |d_col| (d_row, d_col)))
+            .filter(|&offset| offset != (0, 0));
+        let mut live_neighbors: i32 = 0;
+        for ((d_row, d_col), weight) in neighbors.zip(self.weights.iter()) {
+            let idx = ((row + d_row).rem_euclid(height) * width + (col + d_col).rem_euclid(width)) as usize;
+            live_neighbors = live_neighbors.saturating_add(weight * self.assigned[idx] as i32);
+        }
+        let alive = self.rule.next_state(self.assigned[target] == Cell::Alive, live_neighbors);
+        alive == (self.target[target] == Cell::Alive)
+    }
+}
@@ -0,0 +1,340 @@
+//! Setting several `Universe` options from one JSON object.
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::LifeError;
+use crate::rng::Rng;
+use crate::rule::Rule;
+use crate::{check_radius, Boundary, Cell, Universe};
+
+// Fill density `configure` uses when only a `seed` is given.
+const DEFAULT_DENSITY: f64 = 0.5;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Applies every setting in a JSON object at once. Everything is
+    /// checked first: if any field is missing the mark, the board is left
+    /// exactly as it was and the error names the field.
+    ///
+    /// All fields are optional:
+    ///
+    /// - `"width"`, `"height"`: board size, at least 1, with no more cells
+    ///   than fit in a `u32` (a side left out keeps its current length).
+    ///   Resizing clears the board, as `set_width` and `set_height` do.
+    /// - `"rule"`: a rule string such as `"B36/S23"`.
+    /// - `"boundary"`: `"dead"` or `"toroidal"`, as for `set_boundary`.
+    /// - `"neighbor_weights"`: an array of 8 integers, in the order
+    ///   `set_neighbor_weights` takes.
+    /// - `"neighborhood_radius"`: 1 or 2, as for `set_neighborhood_radius`.
+    /// - `"max_population"`: a population cap, or `null` to clear it.
+    /// - `"stop_when_only_spaceships"`: `true` or `false`.
+    /// - `"recording_cap"`: the most deltas a recording keeps.
+    /// - `"density"`, `"seed"`: refill the board at random, with each cell
+    ///   alive with probability `density` (0.5 if left out). A `seed`
+    ///   restarts the board's random stream from that seed first, so the
+    ///   fill is reproducible; without one the fill carries on the stream
+    ///   like `restart`. Refilling starts again from generation 0.
+    pub fn configure(&mut self, json: &str) -> Result<(), LifeError> {
+        let config = Config::parse(json, (self.width, self.height))?;
+        config.apply(self);
+        Ok(())
+    }
+}
+
+// Every setting `configure` accepts, checked but not yet applied.
+#[derive(Default)]
+struct Config {
+    width: Option<u32>,
+    height: Option<u32>,
+    rule: Option<Rule>,
+    boundary: Option<Boundary>,
+    neighbor_weights: Option<[i32; 8]>,
+    neighborhood_radius: Option<u32>,
+    max_population: Option<Option<u32>>,
+    stop_when_only_spaceships: Option<bool>,
+    recording_cap: Option<u32>,
+    density: Option<f64>,
+    seed: Option<u64>,
+}
+
+impl Config {
+    // `size` is the board's current (width, height), which a config giving
+    // only one side is checked against.
+    fn parse(json: &str, size: (u32, u32)) -> Result<Config, LifeError> {
+        let fields = match Json::parse(json)? {
+            Json::Object(fields) => fields,
+            _ => return Err(invalid("config must be a JSON object")),
+        };
+
+        let mut config = Config::default();
+        for (key, value) in fields {
+            match key.as_str() {
+                "width" => config.width = Some(value.size(&key)?),
+                "height" => config.height = Some(value.size(&key)?),
+                "rule" => config.rule = Some(value.string(&key)?.parse()?),
+                "boundary" => {
+                    config.boundary = Some(match value.string(&key)? {
+                        "dead" => Boundary::Dead,
+                        "toroidal" => Boundary::Toroidal,
+                        _ => return Err(invalid("`boundary` must be \"dead\" or \"toroidal\"")),
+                    })
+                }
+                "neighbor_weights" => config.neighbor_weights = Some(value.weights(&key)?),
+                "neighborhood_radius" => {
+                    config.neighborhood_radius = Some(check_radius(value.integer(&key)?)?)
+                }
+                "max_population" => {
+                    config.max_population = Some(match value {
+                        Json::Null => None,
+                        value => Some(value.integer(&key)?),
+                    })
+                }
+                "stop_when_only_spaceships" => {
+                    config.stop_when_only_spaceships = Some(value.boolean(&key)?)
+                }
+                "recording_cap" => config.recording_cap = Some(value.integer(&key)?),
+                "density" => {
+                    let density = value.number(&key)?;
+                    if !(0.0..=1.0).contains(&density) {
+                        return Err(invalid("`density` must be between 0 and 1"));
+                    }
+                    config.density = Some(density);
+                }
+                "seed" => config.seed = Some(value.integer(&key)?),
+                _ => return Err(invalid(&format!("unknown field `{}`", key))),
+            }
+        }
+
+        let width = config.width.unwrap_or(size.0);
+        let height = config.height.unwrap_or(size.1);
+        if width.checked_mul(height).is_none() {
+            return Err(invalid("`width` times `height` is too many cells"));
+        }
+        Ok(config)
+    }
+
+    fn apply(self, universe: &mut Universe) {
+        if let Some(width) = self.width {
+            universe.set_width(width);
+        }
+        if let Some(height) = self.height {
+            universe.set_height(height);
+        }
+        if let Some(rule) = self.rule {
+            universe.rule = rule;
+        }
+        if let Some(boundary) = self.boundary {
+            universe.set_boundary(boundary);
+        }
+        if let Some(weights) = self.neighbor_weights {
+            universe.set_neighbor_weights(weights);
+        }
+        if let Some(radius) = self.neighborhood_radius {
+            universe.neighborhood_radius = radius;
+        }
+        match self.max_population {
+            Some(Some(cap)) => universe.set_max_population(cap),
+            Some(None) => universe.clear_max_population(),
+            None => {}
+        }
+        if let Some(enabled) = self.stop_when_only_spaceships {
+            universe.set_stop_when_only_spaceships(enabled);
+        }
+        if let Some(cap) = self.recording_cap {
+            universe.set_recording_cap(cap);
+        }
+        universe.settings_changed();
+
+        if self.density.is_some() || self.seed.is_some() {
+            let density = self.density.unwrap_or(DEFAULT_DENSITY);
+            if let Some(seed) = self.seed {
+                universe.rng = Rng::new(seed);
+            }
+            for cell in universe.cells.iter_mut() {
+                *cell = if universe.rng.next_f64() < density { Cell::Alive } else { Cell::Dead };
+            }
+            universe.generation = 0;
+            universe.population_history.clear();
+            universe.cells_edited();
+        }
+    }
+}
+
+fn invalid(reason: &str) -> LifeError {
+    LifeError::InvalidConfig(reason.to_string())
+}
+
+// Just enough JSON for a config object. Numbers keep their text so that
+// integers such as seeds don't lose precision going through `f64`.
+enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn parse(text: &str) -> Result<Json, LifeError> {
+        let mut chars = text.chars().peekable();
+        let value = Json::parse_value(&mut chars)?;
+        skip_whitespace(&mut chars);
+        match chars.next() {
+            None => Ok(value),
+            Some(c) => Err(invalid(&format!("unexpected {:?} after the JSON value", c))),
+        }
+    }
+
+    fn parse_value(chars: &mut Chars) -> Result<Json, LifeError> {
+        skip_whitespace(chars);
+        match chars.peek() {
+            Some('{') => {
+                chars.next();
+                let mut fields = Vec::new();
+                skip_whitespace(chars);
+                if chars.peek() == Some(&'}') {
+                    chars.next();
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    skip_whitespace(chars);
+                    let key = parse_string(chars)?;
+                    skip_whitespace(chars);
+                    expect(chars, ':')?;
+                    fields.push((key, Json::parse_value(chars)?));
+                    skip_whitespace(chars);
+                    match chars.next() {
+                        Some(',') => {}
+                        Some('}') => return Ok(Json::Object(fields)),
+                        _ => return Err(invalid("expected `,` or `}` in object")),
+                    }
+                }
+            }
+            Some('[') => {
+                chars.next();
+                let mut items = Vec::new();
+                skip_whitespace(chars);
+                if chars.peek() == Some(&']') {
+                    chars.next();
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(Json::parse_value(chars)?);
+                    skip_whitespace(chars);
+                    match chars.next() {
+                        Some(',') => {}
+                        Some(']') => return Ok(Json::Array(items)),
+                        _ => return Err(invalid("expected `,` or `]` in array")),
+                    }
+                }
+            }
+            Some('"') => Ok(Json::Str(parse_string(chars)?)),
+            Some(c) if *c == '-' || c.is_ascii_digit() => {
+                let mut number = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_digit() || "+-.eE".contains(c) {
+                        number.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                match number.parse::<f64>() {
+                    Ok(_) => Ok(Json::Number(number)),
+                    Err(_) => Err(invalid(&format!("malformed number {:?}", number))),
+                }
+            }
+            Some(_) => {
+                let word: String = std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_alphabetic())).collect();
+                match word.as_str() {
+                    "null" => Ok(Json::Null),
+                    "true" => Ok(Json::Bool(true)),
+                    "false" => Ok(Json::Bool(false)),
+                    _ => Err(invalid("unexpected character in JSON")),
+                }
+            }
+            None => Err(invalid("unexpected end of JSON")),
+        }
+    }
+
+    fn boolean(&self, key: &str) -> Result<bool, LifeError> {
+        match self {
+            Json::Bool(value) => Ok(*value),
+            _ => Err(invalid(&format!("`{}` must be true or false", key))),
+        }
+    }
+
+    fn number(&self, key: &str) -> Result<f64, LifeError> {
+        match self {
+            Json::Number(text) => text.parse().map_err(|_| invalid(&format!("`{}` must be a number", key))),
+            _ => Err(invalid(&format!("`{}` must be a number", key))),
+        }
+    }
+
+    fn integer<T: std::str::FromStr>(&self, key: &str) -> Result<T, LifeError> {
+        match self {
+            Json::Number(text) => text.parse().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| invalid(&format!("`{}` must be a whole number in range", key)))
+    }
+
+    fn size(&self, key: &str) -> Result<u32, LifeError> {
+        match self.integer(key)? {
+            0 => Err(invalid(&format!("`{}` must be at least 1", key))),
+            size => Ok(size),
+        }
+    }
+
+    fn string(&self, key: &str) -> Result<&str, LifeError> {
+        match self {
+            Json::Str(value) => Ok(value),
+            _ => Err(invalid(&format!("`{}` must be a string", key))),
+        }
+    }
+
+    fn weights(&self, key: &str) -> Result<[i32; 8], LifeError> {
+        let mut weights = [0; 8];
+        match self {
+            Json::Array(items) if items.len() == weights.len() => {
+                for (weight, item) in weights.iter_mut().zip(items) {
+                    *weight = item.integer(key)?;
+                }
+                Ok(weights)
+            }
+            _ => Err(invalid(&format!("`{}` must be an array of 8 integers", key))),
+        }
+    }
+}
+
+type Chars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+fn skip_whitespace(chars: &mut Chars) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn expect(chars: &mut Chars, wanted: char) -> Result<(), LifeError> {
+    match chars.next() {
+        Some(c) if c == wanted => Ok(()),
+        _ => Err(invalid(&format!("expected `{}`", wanted))),
+    }
+}
+
+fn parse_string(chars: &mut Chars) -> Result<String, LifeError> {
+    expect(chars, '"')?;
+    let mut string = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(string),
+            Some('\\') => match chars.next() {
+                Some('n') => string.push('\n'),
+                Some('t') => string.push('\t'),
+                Some(c @ ('"' | '\\' | '/')) => string.push(c),
+                _ => return Err(invalid("unsupported escape in string")),
+            },
+            Some(c) => string.push(c),
+            None => return Err(invalid("unterminated string")),
+        }
+    }
+}
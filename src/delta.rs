@@ -0,0 +1,105 @@
+//! Compact differences between two boards of the same size, for sending
+//! updates to a board the other side already has.
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::LifeError;
+use crate::Universe;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Encodes how this board differs from `reference`, which must be the
+    /// same size, so that `apply_delta` on a copy of `reference` gives this
+    /// board back.
+    ///
+    /// The encoding is a sequence of LEB128 varints: the width and height,
+    /// then run lengths over the cells in row-major order, alternating
+    /// between runs of cells that match and runs that differ, starting with
+    /// a (possibly empty) matching run. The final matching run is left out.
+    /// Identical boards encode to just the size.
+    pub fn delta_against(&self, reference: &Universe) -> Result<Vec<u8>, LifeError> {
+        if (self.width, self.height) != (reference.width, reference.height) {
+            return Err(LifeError::SizeMismatch {
+                expected: (reference.width, reference.height),
+                found: (self.width, self.height),
+            });
+        }
+
+        let mut delta = Vec::new();
+        write_varint(&mut delta, u64::from(self.width));
+        write_varint(&mut delta, u64::from(self.height));
+
+        let mut differs = false;
+        let mut run = 0;
+        for (mine, theirs) in self.cells.iter().zip(&reference.cells) {
+            if (mine != theirs) != differs {
+                write_varint(&mut delta, run);
+                differs = !differs;
+                run = 0;
+            }
+            run += 1;
+        }
+        if differs {
+            write_varint(&mut delta, run);
+        }
+        Ok(delta)
+    }
+
+    /// Turns this board, the reference a delta was made against, into the
+    /// board it describes by flipping every cell in a differing run. The
+    /// delta must be for a board of this size. Nothing changes if it's
+    /// malformed.
+    pub fn apply_delta(&mut self, delta: &[u8]) -> Result<(), LifeError> {
+        let mut bytes = delta.iter();
+        let width = read_varint(&mut bytes)?;
+        let height = read_varint(&mut bytes)?;
+        if (width, height) != (u64::from(self.width), u64::from(self.height)) {
+            return Err(LifeError::SizeMismatch {
+                expected: (self.width, self.height),
+                found: (width as u32, height as u32),
+            });
+        }
+
+        let mut flips = Vec::new();
+        let mut idx: u64 = 0;
+        let mut differs = false;
+        while bytes.len() > 0 {
+            let run = read_varint(&mut bytes)?;
+            let end = idx
+                .checked_add(run)
+                .filter(|&end| end <= self.cells.len() as u64)
+                .ok_or(LifeError::InvalidDelta("runs past the end of the board"))?;
+            if differs {
+                flips.push(idx as usize..end as usize);
+            }
+            idx = end;
+            differs = !differs;
+        }
+
+        for idx in flips.into_iter().flatten() {
+            self.cells[idx].toggle();
+        }
+        self.cells_edited();
+        Ok(())
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &mut std::slice::Iter<u8>) -> Result<u64, LifeError> {
+    let mut value: u64 = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.next().ok_or(LifeError::InvalidDelta("ends in the middle of a number"))?;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(LifeError::InvalidDelta("number too large"))
+}
@@ -0,0 +1,78 @@
+use std::fmt;
+
+use wasm_bindgen::JsValue;
+
+/// Everything that can go wrong when configuring or loading a `Universe`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LifeError {
+    /// A rule string that isn't in `B<digits>/S<digits>` form.
+    InvalidRule(String),
+    /// A rendered board row that is `found` cells long instead of the
+    /// `expected` length of the first row.
+    RaggedRow { row: u32, expected: u32, found: u32 },
+    /// A rendered board glyph that is neither the alive nor the dead one.
+    UnknownGlyph(char),
+    /// A board without a single cell.
+    EmptyBoard,
+    /// An argument, named here, that has to be at least 1.
+    ZeroArgument(&'static str),
+    /// Binary board data shorter than its header says it should be.
+    TruncatedBytes { expected: usize, found: usize },
+    /// An RLE file that can't be read, with the reason why.
+    InvalidRle(String),
+    /// Another board that is `found` (width, height) instead of the
+    /// `expected` size of this one.
+    SizeMismatch { expected: (u32, u32), found: (u32, u32) },
+    /// A `configure` object that can't be applied, with the reason why.
+    InvalidConfig(String),
+    /// A neighbourhood radius whose neighbour counts don't fit in a rule.
+    RadiusTooLarge(u32),
+    /// Board delta bytes that can't be decoded, with the reason why.
+    InvalidDelta(&'static str),
+    /// An argument, named here, too large to work with.
+    ArgumentTooLarge(&'static str),
+    /// Something the board's current settings rule out, with the reason why.
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for LifeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LifeError::InvalidRule(rule) => write!(f, "invalid rule string: {:?}", rule),
+            LifeError::RaggedRow { row, expected, found } => write!(
+                f,
+                "row {} is {} cells long, expected {}",
+                row, found, expected
+            ),
+            LifeError::UnknownGlyph(glyph) => write!(f, "unknown cell glyph: {:?}", glyph),
+            LifeError::EmptyBoard => write!(f, "board has no cells"),
+            LifeError::ZeroArgument(name) => write!(f, "`{}` must be at least 1", name),
+            LifeError::TruncatedBytes { expected, found } => write!(
+                f,
+                "board data is {} bytes long, expected {}",
+                found, expected
+            ),
+            LifeError::InvalidRle(reason) => write!(f, "invalid RLE: {}", reason),
+            LifeError::SizeMismatch { expected, found } => write!(
+                f,
+                "board is {}x{}, expected {}x{}",
+                found.0, found.1, expected.0, expected.1
+            ),
+            LifeError::InvalidConfig(reason) => write!(f, "invalid config: {}", reason),
+            LifeError::RadiusTooLarge(radius) => {
+                write!(f, "neighbourhood radius {} is too large, the most is 2", radius)
+            }
+            LifeError::InvalidDelta(reason) => write!(f, "invalid board delta: {}", reason),
+            LifeError::ArgumentTooLarge(name) => write!(f, "`{}` is too large", name),
+            LifeError::Unsupported(reason) => write!(f, "not supported: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for LifeError {}
+
+impl From<LifeError> for JsValue {
+    fn from(error: LifeError) -> JsValue {
+        JsValue::from_str(&error.to_string())
+    }
+}
@@ -1,33 +1,91 @@
+mod analysis;
+mod config;
+mod delta;
+mod error;
+mod font;
+mod perf;
+mod rle;
+mod rng;
+mod rule;
 mod utils;
 
+pub use error::LifeError;
+pub use perf::PerfCounters;
+pub use rle::RleParser;
+use rng::Rng;
+use rule::Rule;
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
 use wasm_bindgen::prelude::*;
 
 extern crate js_sys;
 extern crate web_sys;
 
-// A macro to provide `println!(..)`-style syntax for `console.log` logging.
+// A macro to provide `println!(..)`-style syntax for `console.log` logging,
+// or for the sink set with `set_log_sink`.
+#[allow(unused_macros)]
 macro_rules! log {
     ( $( $t:tt )* ) => {
-        web_sys::console::log_1(&format!( $( $t )* ).into());
+        crate::emit_log(&format!( $( $t )* ));
     }
 }
 
+use std::cell::RefCell;
 use web_sys::console;
 
+thread_local! {
+    static LOG_SINK: RefCell<Option<js_sys::Function>> = RefCell::new(None);
+}
+
+/// Routes `log!` output and `Timer` measurements to `sink`, called with
+/// one string argument per message, instead of the console. Passing
+/// `undefined` goes back to the console.
+#[wasm_bindgen]
+pub fn set_log_sink(sink: Option<js_sys::Function>) {
+    LOG_SINK.with(|cell| *cell.borrow_mut() = sink);
+}
+
+fn has_log_sink() -> bool {
+    LOG_SINK.with(|cell| cell.borrow().is_some())
+}
+
+fn emit_log(message: &str) {
+    // Called outside the borrow, so a sink that logs or swaps the sink
+    // itself doesn't find the cell still borrowed.
+    let sink = LOG_SINK.with(|cell| cell.borrow().clone());
+    match sink {
+        Some(sink) => {
+            let _ = sink.call1(&JsValue::NULL, &JsValue::from_str(message));
+        }
+        None => console::log_1(&message.into()),
+    }
+}
+
 pub struct Timer<'a> {
     name: &'a str,
+    // Start time when reporting to the log sink, which has no
+    // `console.time` of its own.
+    started: Option<f64>,
 }
 
 impl<'a> Timer<'a> {
     pub fn new(name: &'a str) -> Timer<'a> {
+        if has_log_sink() {
+            return Timer { name, started: Some(js_sys::Date::now()) };
+        }
         console::time_with_label(name);
-        Timer { name }
+        Timer { name, started: None }
     }
 }
 
 impl<'a> Drop for Timer<'a> {
     fn drop(&mut self) {
-        console::time_end_with_label(self.name);
+        match self.started {
+            Some(started) => emit_log(&format!("{}: {}ms", self.name, js_sys::Date::now() - started)),
+            None => console::time_end_with_label(self.name),
+        }
     }
 }
 
@@ -39,17 +97,119 @@ static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
 #[wasm_bindgen]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Cell {
     Dead = 0,
     Alive = 1,
 }
 
+/// What lies past the edges of the board when counting neighbours.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Boundary {
+    /// Nothing: cells past an edge are always dead.
+    Dead = 0,
+    /// The opposite edge, so the board wraps round like a torus.
+    Toroidal = 1,
+}
+
+// Largest `Universe::set_neighborhood_radius`, the widest square whose
+// neighbour count (24) still fits the rule's 32-bit masks.
+const MAX_NEIGHBORHOOD_RADIUS: u32 = 2;
+
+// `radius` if `Universe::set_neighborhood_radius` can take it.
+fn check_radius(radius: u32) -> Result<u32, LifeError> {
+    match radius {
+        0 => Err(LifeError::ZeroArgument("radius")),
+        radius if radius > MAX_NEIGHBORHOOD_RADIUS => Err(LifeError::RadiusTooLarge(radius)),
+        radius => Ok(radius),
+    }
+}
+
+// A fresh seed for a board's random stream. Two draws, since one
+// `Math::random` only carries 53 random bits.
+fn random_seed() -> u64 {
+    let seed = (js_sys::Math::random() * 2f64.powi(32)) as u64;
+    seed << 32 | (js_sys::Math::random() * 2f64.powi(32)) as u64
+}
+
+// Board size used by `Universe::setup_and_gate`, large enough that none of
+// the streams wrap around before they are annihilated or eaten.
+const AND_GATE_WIDTH: u32 = 160;
+const AND_GATE_HEIGHT: u32 = 112;
+
+// Gosper glider gun, emitting a glider towards the south-east every 30
+// generations.
+const GOSPER_GLIDER_GUN: [(i64, i64); 36] = [
+    (0, 24),
+    (1, 22), (1, 24),
+    (2, 12), (2, 13), (2, 20), (2, 21), (2, 34), (2, 35),
+    (3, 11), (3, 15), (3, 20), (3, 21), (3, 34), (3, 35),
+    (4, 0), (4, 1), (4, 10), (4, 16), (4, 20), (4, 21),
+    (5, 0), (5, 1), (5, 10), (5, 14), (5, 16), (5, 17), (5, 22), (5, 24),
+    (6, 10), (6, 16), (6, 24),
+    (7, 11), (7, 15),
+    (8, 12), (8, 13),
+];
+
+// Eater 1, which swallows gliders arriving from the north-west.
+const EATER: [(i64, i64); 7] = [(0, 0), (0, 1), (1, 0), (1, 2), (2, 2), (3, 2), (3, 3)];
+
+// Number of generations kept in the population history.
+const POPULATION_HISTORY_LEN: usize = 1024;
+
+// Default generations between snapshots in `Universe::precompute`.
+const DEFAULT_SNAPSHOT_INTERVAL: u32 = 32;
+
+// Default number of generations kept by `Universe::start_recording`.
+const DEFAULT_RECORDING_CAP: u32 = 1024;
+
+// Board at the start of a recording plus the cells each tick flipped since.
+#[derive(Clone)]
+struct Recording {
+    start: Vec<Cell>,
+    deltas: VecDeque<Vec<u32>>,
+}
+
+// The board at one generation, saved by `Universe::precompute`.
+#[derive(Clone)]
+struct Snapshot {
+    generation: u64,
+    cells: Vec<Cell>,
+}
+
+// A generation `Universe::tick_budget` has computed the first `done` cells of.
+#[derive(Clone)]
+struct PartialTick {
+    next: Vec<Cell>,
+    done: usize,
+}
+
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct Universe {
     width: u32,
     height: u32,
     cells: Vec<Cell>,
+    generation: u64,
+    population_history: VecDeque<(u64, u32)>,
+    rule: Rule,
+    rule_schedule: Vec<(u64, Rule)>,
+    recording: Option<Recording>,
+    recording_cap: u32,
+    stop_when_only_spaceships: bool,
+    max_population: Option<u32>,
+    population_cap_hit: bool,
+    neighbor_weights: [i32; 8],
+    dirty_rect: Option<(u32, u32, u32, u32)>,
+    partial_tick: Option<PartialTick>,
+    boundary: Boundary,
+    wrap_after: Option<u64>,
+    neighborhood_radius: u32,
+    rng: Rng,
+    snapshots: Vec<Snapshot>,
+    snapshot_interval: u32,
+    perf: PerfCounters,
 }
 
 #[wasm_bindgen]
@@ -58,30 +218,15 @@ impl Universe {
 	pub fn new() -> Universe {
 		//utils::set_panic_hook();
 		
-        let width = 128;
-        let height = 128;
-
-        let cells = (0..width * height)
-            .map(|_i| {
-                if js_sys::Math::random() < 0.5 {
-                    Cell::Alive
-                } else {
-                    Cell::Dead
-                }
-            })
-            .collect();
-
-        Universe {
-            width,
-            height,
-            cells,
-        }
+        let mut universe = Universe::dead(128, 128);
+        universe.restart();
+        universe
     }
 	
 	pub fn restart(&mut self) {
 		let cells = (0..self.width * self.height)
             .map(|_i| {
-                if js_sys::Math::random() < 0.5 {
+                if self.rng.next_f64() < 0.5 {
                     Cell::Alive
                 } else {
                     Cell::Dead
@@ -90,155 +235,940 @@ impl Universe {
             .collect();
 			
 		self.cells = cells;	
+		self.generation = 0;
+		self.population_history.clear();
+		self.cells_edited();
 	}
 
     pub fn render(&self) -> String {
         self.to_string()
     }
+
+	/// The largest whole number of pixels per cell that fits the board into
+	/// a `canvas_px` square with square cells, but never less than 1.
+	pub fn cell_pitch(&self, canvas_px: u32) -> u32 {
+		let across = canvas_px.checked_div(self.width).unwrap_or(canvas_px);
+		let down = canvas_px.checked_div(self.height).unwrap_or(canvas_px);
+		across.min(down).max(1)
+	}
+
+	/// Draws the board into a `canvas_px` square RGBA buffer, ready for an
+	/// `ImageData`, with `cell_pitch` sized cells and the board centred.
+	/// Colours are `0xRRGGBBAA`; `background` fills the margin around the
+	/// board. A board too big even at a pitch of 1 is cut off at the edges.
+	pub fn render_centered_rgba(&self, canvas_px: u32, alive: u32, dead: u32, background: u32) -> Vec<u8> {
+		let pitch = self.cell_pitch(canvas_px);
+		let left = canvas_px.saturating_sub(self.width * pitch) / 2;
+		let top = canvas_px.saturating_sub(self.height * pitch) / 2;
+
+		let mut pixels = Vec::with_capacity(canvas_px as usize * canvas_px as usize * 4);
+		for y in 0..canvas_px {
+			for x in 0..canvas_px {
+				let row = y.checked_sub(top).map(|dy| dy / pitch).filter(|&row| row < self.height);
+				let col = x.checked_sub(left).map(|dx| dx / pitch).filter(|&col| col < self.width);
+				let color = match (row, col) {
+					(Some(row), Some(col)) => match self.cells[self.get_index(row, col)] {
+						Cell::Alive => alive,
+						Cell::Dead => dead,
+					},
+					_ => background,
+				};
+				pixels.extend_from_slice(&color.to_be_bytes());
+			}
+		}
+		pixels
+	}
+
+	/// Renders the current board and then each of the next `steps`
+	/// generations, `cell_px` pixels square per cell in `0xRRGGBBAA`
+	/// colours, into one buffer for exporting an animation. The board is
+	/// left `steps` generations on.
+	///
+	/// The buffer starts with a 12-byte header of three little-endian
+	/// `u32`s: the number of frames (`steps + 1`), and the width and height
+	/// of a frame in pixels. The frames follow back to back, each
+	/// `width * height * 4` bytes of row-major RGBA.
+	pub fn render_frames_rgba(&mut self, steps: u32, cell_px: u32, alive: u32, dead: u32) -> Vec<u8> {
+		let frame_len = self.cells.len() * cell_px as usize * cell_px as usize * 4;
+		let mut frames = Vec::with_capacity(FRAMES_HEADER_LEN + (steps as usize + 1) * frame_len);
+		self.push_frames_header(&mut frames, steps + 1, cell_px);
+		for step in 0..=steps {
+			if step > 0 {
+				self.tick(0);
+			}
+			frames.extend(self.rgba_image(cell_px, |idx| self.cells[idx].rgba(alive, dead)));
+		}
+		frames
+	}
+
+	/// Like `render_frames_rgba`, but a generation only gets a frame if the
+	/// tick that produced it changed the board, so a board that stands
+	/// still for a while doesn't repeat the same frame. Runs `max_steps`
+	/// ticks and always includes the current board as the first frame.
+	///
+	/// The layout is the same: a 12-byte header of little-endian `u32`s
+	/// holding the number of frames actually written and the frame width
+	/// and height in pixels, then the RGBA frames back to back.
+	pub fn tick_until_change_frames(&mut self, max_steps: u32, cell_px: u32, alive: u32, dead: u32) -> Vec<u8> {
+		let mut frames = Vec::new();
+		self.push_frames_header(&mut frames, 0, cell_px);
+		let mut frame_count: u32 = 0;
+		for step in 0..=max_steps {
+			if step > 0 {
+				self.tick(0);
+				if self.dirty_rect.is_none() {
+					continue;
+				}
+			}
+			frames.extend(self.rgba_image(cell_px, |idx| self.cells[idx].rgba(alive, dead)));
+			frame_count += 1;
+		}
+		frames[..4].copy_from_slice(&frame_count.to_le_bytes());
+		frames
+	}
+
+	/// An RGBA image, `cell_px` pixels square per cell, of how the board
+	/// moved over the last `lag` generations: cells alive then and now are
+	/// drawn `stayed`, cells only alive now `appeared` and cells only alive
+	/// then `vanished`, with every other pixel transparent. Colours are
+	/// `0xRRGGBBAA`.
+	///
+	/// The past board comes from the recording, so `start_recording` has to
+	/// have been running for at least `lag` generations (and the recording
+	/// cap must be at least `lag`). Without that much history the result is
+	/// empty.
+	pub fn motion_rgba(&self, lag: u32, cell_px: u32, stayed: u32, appeared: u32, vanished: u32) -> Vec<u8> {
+		let past = match self.recorded_past(lag) {
+			Some(past) => past,
+			None => return Vec::new(),
+		};
+		self.rgba_image(cell_px, |idx| match (past[idx], self.cells[idx]) {
+			(Cell::Alive, Cell::Alive) => stayed,
+			(Cell::Dead, Cell::Alive) => appeared,
+			(Cell::Alive, Cell::Dead) => vanished,
+			(Cell::Dead, Cell::Dead) => 0,
+		})
+	}
+
+	/// Blends this board with `other` into an RGBA buffer of one pixel per
+	/// cell, for a dissolve between two saved boards. Each cell is drawn
+	/// black when alive and white when dead, and every channel is mixed as
+	/// `round((1 - t) * self + t * other)`, with `t` clamped to `0.0..=1.0`.
+	/// Both boards must be the same size.
+	pub fn crossfade(&self, other: &Universe, t: f64) -> Result<Vec<u8>, LifeError> {
+		if (self.width, self.height) != (other.width, other.height) {
+			return Err(LifeError::SizeMismatch {
+				expected: (self.width, self.height),
+				found: (other.width, other.height),
+			});
+		}
+
+		let t = t.clamp(0.0, 1.0);
+		let mut pixels = Vec::with_capacity(self.cells.len() * 4);
+		for (&from, &to) in self.cells.iter().zip(&other.cells) {
+			for (&a, &b) in cell_rgba(from).iter().zip(&cell_rgba(to)) {
+				pixels.push(((1.0 - t) * f64::from(a) + t * f64::from(b)).round() as u8);
+			}
+		}
+		Ok(pixels)
+	}
+
+	/// Parses text in the format `render` produces, one line of glyphs per
+	/// row, back into a board of the same size. `alive` and `dead` are the
+	/// glyphs to read, so `from_render(&u.render(), '◼', '◻')` round-trips.
+	/// Every row must be as long as the first one.
+	pub fn from_render(text: &str, alive: char, dead: char) -> Result<Universe, LifeError> {
+		let rows: Vec<&str> = text.lines().collect();
+		let width = rows.first().map_or(0, |row| row.chars().count()) as u32;
+		if width == 0 {
+			return Err(LifeError::EmptyBoard);
+		}
+
+		let mut universe = Universe::dead(width, rows.len() as u32);
+		for (row, line) in rows.iter().enumerate() {
+			let found = line.chars().count() as u32;
+			if found != width {
+				return Err(LifeError::RaggedRow { row: row as u32, expected: width, found });
+			}
+			for (col, glyph) in line.chars().enumerate() {
+				let idx = row * width as usize + col;
+				universe.cells[idx] = match glyph {
+					g if g == alive => Cell::Alive,
+					g if g == dead => Cell::Dead,
+					g => return Err(LifeError::UnknownGlyph(g)),
+				};
+			}
+		}
+		Ok(universe)
+	}
 	
+	/// Advances the board by one generation.
+	///
+	/// Every cell of the next generation is computed from the current one,
+	/// which stays frozen for the whole tick: results are written to a
+	/// separate buffer that only replaces `cells` once all of them are done,
+	/// so no cell ever reads a neighbour that has already been updated.
 	pub fn tick(&mut self, range: u32) {
 		let _timer = Timer::new("Universe::tick");
 
-		let mut next = {
+		self.partial_tick = None;
+		if !self.begin_generation() {
+			return;
+		}
+
+		let next = {
 			let _timer = Timer::new("allocate next cells");
+			self.perf.count_allocation();
 			self.cells.clone()
 		};
 
-		{
+		let next = {
 			let _timer = Timer::new("new generation");
-			for row in range..self.height {
-				for col in range..self.width {
-					let idx = self.get_index(row, col);
-					let cell = self.cells[idx];
-					let live_neighbors = self.live_neighbor_count(row, col);
-
-					let next_cell = match (cell, live_neighbors) {
-						// Rule 1: Any live cell with fewer than two live neighbours
-						// dies, as if caused by underpopulation.
-						(Cell::Alive, x) if x < 2 => Cell::Dead,
-						// Rule 2: Any live cell with two or three live neighbours
-						// lives on to the next generation.
-						(Cell::Alive, 2) | (Cell::Alive, 3) => Cell::Alive,
-						// Rule 3: Any live cell with more than three live
-						// neighbours dies, as if by overpopulation.
-						(Cell::Alive, x) if x > 3 => Cell::Dead,
-						// Rule 4: Any dead cell with exactly three live neighbours
-						// becomes a live cell, as if by reproduction.
-						(Cell::Dead, 3) => Cell::Alive,
-						// All other cells remain in the same state.
-						(otherwise, _) => otherwise,
-					};
-
-					next[idx] = next_cell;
+			self.next_generation(next, range)
+		};
+
+		let old = self.finish_generation(next);
+		let _timer = Timer::new("free old cells");
+		drop(old);
+	}
+
+	/// Works on the next generation for at most `max_cells` cells, picking
+	/// up where the previous call stopped, and returns `true` once a whole
+	/// generation has been computed and applied. Spreading a tick over a
+	/// few animation frames keeps huge boards from stalling the page.
+	///
+	/// Nothing is visible until the generation is complete: `cells` and
+	/// every other getter keep showing the previous generation until the
+	/// call that returns `true`. A plain `tick`, or any edit to the board
+	/// or its settings in between, throws away a half-finished generation
+	/// and the next call starts it again.
+	pub fn tick_budget(&mut self, max_cells: u32) -> bool {
+		let mut partial = match self.partial_tick.take() {
+			Some(partial) => partial,
+			None => {
+				if !self.begin_generation() {
+					return true;
 				}
+				self.perf.count_allocation();
+				PartialTick { next: self.cells.clone(), done: 0 }
 			}
+		};
+
+		let rule = self.scheduled_rule();
+		let end = partial.done.saturating_add(max_cells as usize).min(partial.next.len());
+		for idx in partial.done..end {
+			let (row, col) = (idx as u32 / self.width, idx as u32 % self.width);
+			partial.next[idx] = self.next_cell(row, col, rule);
 		}
+		self.perf.count_cells(end - partial.done);
+		partial.done = end;
 
-		let _timer = Timer::new("free old cells");
-		self.cells = next;
+		if partial.done == partial.next.len() {
+			self.finish_generation(partial.next);
+			true
+		} else {
+			self.partial_tick = Some(partial);
+			false
+		}
 	}
-	
-	fn get_index(&self, row: u32, column: u32) -> usize {
-        let (_row_normalize, _column_normalize) = self.normalize_coordinate(row, column);
-		(_row_normalize * self.width + _column_normalize) as usize
-    }
-	
-	fn normalize_coordinate(&self, mut row: u32, mut col: u32) -> (u32, u32) {
-		
-		if row < 0 {
-			row = self.height - 1;
-		} else if row > self.height - 1{
-			row = 0;
+
+	/// Flat indices of the cells the next `tick` would flip, in ascending
+	/// order, without touching the board. Uses the rule and boundary that
+	/// tick would apply, so scheduled changes to either are taken into
+	/// account.
+	pub fn pending_changes(&self) -> Vec<u32> {
+		let rule = self.scheduled_rule();
+		let boundary = self.scheduled_boundary();
+		let mut changes = Vec::new();
+		for row in 0..self.height {
+			for col in 0..self.width {
+				let idx = self.get_index(row, col);
+				let alive = self.cells[idx] == Cell::Alive;
+				if rule.next_state(alive, self.weighted_count(row, col, boundary)) != alive {
+					changes.push(idx as u32);
+				}
+			}
+		}
+		changes
+	}
+
+	/// The `[min_row, min_col, max_row, max_col]` rectangle of the cells
+	/// that changed in the last `tick`, or `None` (`undefined` in JS) if
+	/// none did. JS can re-blit just this part of the canvas.
+	pub fn dirty_rect(&self) -> Option<Vec<u32>> {
+		self.dirty_rect
+			.map(|(min_row, min_col, max_row, max_col)| vec![min_row, min_col, max_row, max_col])
+	}
+
+	/// Tick once, then rotate the torus so the live cells' centre of mass
+	/// sits in the middle of the board, and return the `[d_row, d_col]`
+	/// passed to `rotate_rows` and `rotate_cols`. This moves the cells
+	/// themselves (wrapping around the edges), so a ship roaming the
+	/// board stays on screen. An empty board isn't moved.
+	pub fn tick_and_recenter(&mut self) -> Vec<i32> {
+		self.tick(0);
+
+		let (center_row, center_col) = match self.center_of_mass() {
+			Some(center) => center,
+			None => return vec![0, 0],
 		};
-		
-		if col < 0 {
-			col = self.width - 1;
-		} else if col > self.width - 1 {
-			col = 0;
+		let shift = |center: f64, size: u32| -> i32 {
+			let size = size as i32;
+			let by = (size / 2 - center.round() as i32).rem_euclid(size);
+			// Take the shorter way round.
+			if by > size / 2 {
+				by - size
+			} else {
+				by
+			}
 		};
-		
-		(row, col)
+		let (d_row, d_col) = (shift(center_row, self.height), shift(center_col, self.width));
+
+		self.rotate_rows(d_row);
+		self.rotate_cols(d_col);
+		vec![d_row, d_col]
 	}
-	
-	fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
-        let mut count = 0;
 
-		let north = if row == 0 {
-			self.height - 1
-		} else {
-			row - 1
-		};
+	pub fn tick_many(&mut self, steps: u32) {
+		for _ in 0..steps {
+			self.tick(0);
+		}
+	}
 
-		let south = if row == self.height - 1 {
-			0
-		} else {
-			row + 1
-		};
+	/// Advances `steps` generations and returns the resulting board in the
+	/// `to_bytes` format, in a single call for a web worker to post back.
+	/// This board is left at the final generation.
+	pub fn autoplay_to_bytes(&mut self, steps: u32) -> Vec<u8> {
+		self.tick_many(steps);
+		self.to_bytes()
+	}
 
-		let west = if column == 0 {
-			self.width - 1
-		} else {
-			column - 1
+	/// The board in a compact binary format: width and height as
+	/// little-endian `u32`s, followed by the cells in row-major order packed
+	/// eight to a byte, least significant bit first, with 1 for alive.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::with_capacity(8 + self.cells.len().div_ceil(8));
+		bytes.extend_from_slice(&self.width.to_le_bytes());
+		bytes.extend_from_slice(&self.height.to_le_bytes());
+		for chunk in self.cells.chunks(8) {
+			let packed = chunk
+				.iter()
+				.enumerate()
+				.fold(0u8, |byte, (bit, &cell)| byte | (cell as u8) << bit);
+			bytes.push(packed);
+		}
+		bytes
+	}
+
+	/// Reads a board written by `to_bytes`.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Universe, LifeError> {
+		let header = |at: usize| -> Result<u32, LifeError> {
+			let mut word = [0; 4];
+			word.copy_from_slice(bytes.get(at..at + 4).ok_or(LifeError::TruncatedBytes {
+				expected: 8,
+				found: bytes.len(),
+			})?);
+			Ok(u32::from_le_bytes(word))
 		};
+		let (width, height) = (header(0)?, header(4)?);
+		if width == 0 || height == 0 {
+			return Err(LifeError::EmptyBoard);
+		}
+
+		// A size too large to count can't have all its bytes present either.
+		let expected = width
+			.checked_mul(height)
+			.map_or(usize::MAX, |count| 8 + (count as usize).div_ceil(8));
+		if bytes.len() < expected {
+			return Err(LifeError::TruncatedBytes { expected, found: bytes.len() });
+		}
+
+		let mut universe = Universe::dead(width, height);
+		for (idx, cell) in universe.cells.iter_mut().enumerate() {
+			if bytes[8 + idx / 8] & (1 << (idx % 8)) != 0 {
+				*cell = Cell::Alive;
+			}
+		}
+		Ok(universe)
+	}
+
+	/// Shifts every cell `by` rows down the torus (up when negative); cells
+	/// pushed off the bottom come back in at the top.
+	pub fn rotate_rows(&mut self, by: i32) {
+		let shift = (by.rem_euclid(self.height as i32) * self.width as i32) as usize;
+		self.cells.rotate_right(shift);
+		self.cells_edited();
+	}
+
+	/// Shifts every cell `by` columns right along the torus (left when
+	/// negative); cells pushed off the right edge come back in on the left.
+	pub fn rotate_cols(&mut self, by: i32) {
+		let shift = by.rem_euclid(self.width as i32) as usize;
+		for row in self.cells.chunks_mut(self.width as usize) {
+			row.rotate_right(shift);
+		}
+		self.cells_edited();
+	}
+
+	/// Pokes the board: every cell within `radius` of `(row, col)`, by
+	/// straight-line distance, is flipped with even odds, drawn from a
+	/// generator seeded with `seed` so the same poke always scatters the
+	/// same way. Around an edge the disturbance wraps round with a
+	/// `Toroidal` boundary and is cut off with a `Dead` one. The board
+	/// changes at once, without advancing a generation; the next ticks
+	/// carry the ripple outwards.
+	pub fn explode(&mut self, row: u32, col: u32, radius: u32, seed: u64) {
+		let mut rng = Rng::new(seed);
+		let radius = i64::from(radius);
+		let (height, width) = (i64::from(self.height), i64::from(self.width));
+		for dr in -radius..=radius {
+			for dc in -radius..=radius {
+				if dr * dr + dc * dc > radius * radius || rng.next_f64() >= 0.5 {
+					continue;
+				}
+				let (r, c) = (i64::from(row) + dr, i64::from(col) + dc);
+				let inside = (0..height).contains(&r) && (0..width).contains(&c);
+				if inside || self.scheduled_boundary() == Boundary::Toroidal {
+					let idx = self.get_index(r.rem_euclid(height) as u32, c.rem_euclid(width) as u32);
+					self.cells[idx].toggle();
+				}
+			}
+		}
+		self.cells_edited();
+	}
+
+	/// Kills every live cell that has no live neighbours at all, in one
+	/// pass and without advancing the generation. Those cells would die on
+	/// the next `tick` anyway; this clears them now, for tidying up a noisy
+	/// board before analysing it. Neighbour weights are ignored here.
+	pub fn despeckle(&mut self) {
+		let boundary = self.scheduled_boundary();
+		let isolated: Vec<usize> = (0..self.height)
+			.flat_map(|row| (0..self.width).map(move |col| (row, col)))
+			.map(|(row, col)| (self.get_index(row, col), row, col))
+			.filter(|&(idx, row, col)| self.cells[idx] == Cell::Alive && !self.has_live_neighbor(row, col, boundary))
+			.map(|(idx, _, _)| idx)
+			.collect();
+		for idx in isolated {
+			self.cells[idx] = Cell::Dead;
+		}
+		self.cells_edited();
+	}
+
+	/// A smaller copy for thumbnails, where each `factor` x `factor` block
+	/// of cells becomes one cell. With `majority` off a block is alive if
+	/// any of its cells is; with it on, only if more than half of them are.
+	/// Rows and columns left over past the last whole block are dropped.
+	pub fn downsample(&self, factor: u32, majority: bool) -> Result<Universe, LifeError> {
+		if factor == 0 {
+			return Err(LifeError::ZeroArgument("factor"));
+		}
+		let (width, height) = (self.width / factor, self.height / factor);
+		if width == 0 || height == 0 {
+			return Err(LifeError::EmptyBoard);
+		}
+
+		let mut thumbnail = Universe::dead(width, height);
+		for row in 0..height {
+			for col in 0..width {
+				let alive = (0..factor)
+					.flat_map(|r| (0..factor).map(move |c| (r, c)))
+					.filter(|&(r, c)| {
+						self.cells[self.get_index(row * factor + r, col * factor + c)] == Cell::Alive
+					})
+					.count() as u32;
+				let block_alive = if majority { 2 * alive > factor * factor } else { alive > 0 };
+				if block_alive {
+					thumbnail.cells[(row * width + col) as usize] = Cell::Alive;
+				}
+			}
+		}
+		Ok(thumbnail)
+	}
+
+	/// Refuses to advance past `cap` live cells: a `tick` whose next
+	/// generation would be more crowded leaves the board as it is and sets
+	/// `population_cap_hit`. There's no cap until this is called.
+	pub fn set_max_population(&mut self, cap: u32) {
+		self.max_population = Some(cap);
+		self.settings_changed();
+	}
+
+	pub fn clear_max_population(&mut self) {
+		self.max_population = None;
+		self.population_cap_hit = false;
+		self.settings_changed();
+	}
+
+	/// Whether the last `tick` was held back by `set_max_population`.
+	pub fn population_cap_hit(&self) -> bool {
+		self.population_cap_hit
+	}
+
+	/// When enabled, `tick` leaves the board alone once it holds nothing but
+	/// spaceships, so a soup run stops as soon as only escaping gliders and
+	/// ships remain. Checking this costs every tick a pass to split the board
+	/// into objects plus a short isolated simulation of each object (up to
+	/// 15 generations on a board a little larger than the object), so it's
+	/// off by default.
+	pub fn set_stop_when_only_spaceships(&mut self, enabled: bool) {
+		self.stop_when_only_spaceships = enabled;
+		self.settings_changed();
+	}
+
+	/// The boundary in effect, which is the one the next tick uses: the
+	/// `set_wrap_after` schedule's if there is one, otherwise the one from
+	/// `set_boundary`, `Toroidal` unless set otherwise.
+	pub fn boundary(&self) -> Boundary {
+		self.scheduled_boundary()
+	}
+
+	/// Switches the boundary for every tick from now on, replacing a
+	/// `set_wrap_after` schedule. Boards wrap round (`Toroidal`) by default.
+	pub fn set_boundary(&mut self, boundary: Boundary) {
+		self.boundary = boundary;
+		self.wrap_after = None;
+		self.settings_changed();
+	}
+
+	/// Runs the board with a `Dead` boundary until it reaches `generation`,
+	/// then joins the edges up into a torus. The tick from `generation` to
+	/// `generation + 1` is the first to wrap round, so 0 (or any generation
+	/// already reached) wraps from the next tick. `boundary` and every edit
+	/// that depends on it follow the schedule straight away.
+	pub fn set_wrap_after(&mut self, generation: u64) {
+		self.wrap_after = Some(generation);
+		self.settings_changed();
+	}
+
+	pub fn neighborhood_radius(&self) -> u32 {
+		self.neighborhood_radius
+	}
+
+	/// Counts neighbours over the square `radius` cells out on every side
+	/// instead of just the adjacent ones: 8 neighbours at radius 1, 24 at
+	/// radius 2. Past radius 1 every neighbour counts once and the weights
+	/// from `set_neighbor_weights` are ignored. Rules only go up to a count
+	/// of 31, so the radius can be at most 2; use `set_rule_counts` (or the
+	/// comma form of `set_rule`) for counts above 8.
+	pub fn set_neighborhood_radius(&mut self, radius: u32) -> Result<(), LifeError> {
+		self.neighborhood_radius = check_radius(radius)?;
+		self.settings_changed();
+		Ok(())
+	}
+
+	/// Switches to the rule where a dead cell is born with any of the
+	/// `birth` counts of live neighbours and a live one survives with any
+	/// of the `survival` counts. Counts go up to 31.
+	pub fn set_rule_counts(&mut self, birth: &[u32], survival: &[u32]) -> Result<(), LifeError> {
+		self.rule = Rule::from_counts(birth, survival)?;
+		self.settings_changed();
+		Ok(())
+	}
+
+	/// The full state of the board's random stream, which `restart` and
+	/// random fills draw from. Save it with the board and hand it back to
+	/// `set_rng_state` to make every later random draw come out the same.
+	///
+	/// This is not a seed: a seed (as in `configure`) picks where a stream
+	/// starts, while this captures where it has got to since.
+	pub fn rng_state(&self) -> u64 {
+		self.rng.state()
+	}
+
+	/// Continues the random stream from a state saved with `rng_state`.
+	/// A state of 0 can't come from `rng_state` and is swapped for a fixed
+	/// non-zero one.
+	pub fn set_rng_state(&mut self, state: u64) {
+		self.rng = Rng::from_state(state);
+		self.settings_changed();
+	}
+
+	/// How many ticks from now the population first goes above `threshold`,
+	/// looking at most `max_gen` ticks ahead, or `None` if it never does.
+	/// 0 means it already is. The run happens on a copy; this board is left
+	/// as it is.
+	pub fn generation_exceeding(&self, threshold: u32, max_gen: u32) -> Option<u32> {
+		if self.live_count() > threshold {
+			return Some(0);
+		}
+
+		let mut scratch = self.scratch();
+		for generation in 1..=max_gen {
+			scratch.step();
+			if scratch.live_count() > threshold {
+				return Some(generation);
+			}
+		}
+		None
+	}
+
+	/// How closely the board repeats after `lag` generations: of the cells
+	/// alive now or `lag` generations from now, the fraction alive at both
+	/// times. Dead space doesn't count either way, so a lone cell dying on
+	/// a large board scores 0.0, not nearly 1.0. 1.0 means the board
+	/// repeats exactly with period `lag` (or a divisor of it), values close
+	/// to 1.0 hint at a near period; a board with nothing alive at either
+	/// time repeats trivially and also gives 1.0. The run happens on a
+	/// copy; this board is left as it is.
+	pub fn autocorrelation(&mut self, lag: u32) -> f64 {
+		let mut scratch = self.scratch();
+		for _ in 0..lag {
+			scratch.step();
+		}
+
+		let (mut alive_either, mut alive_both) = (0, 0);
+		for (&now, &later) in self.cells.iter().zip(&scratch.cells) {
+			if now == Cell::Alive || later == Cell::Alive {
+				alive_either += 1;
+			}
+			if now == Cell::Alive && later == Cell::Alive {
+				alive_both += 1;
+			}
+		}
+		if alive_either == 0 {
+			return 1.0;
+		}
+		alive_both as f64 / alive_either as f64
+	}
+
+	/// Starts logging the cells every tick flips, keeping a copy of the
+	/// current board to replay them from. Restarting drops the old log, and
+	/// so does any change to the cells other than a tick, such as
+	/// `toggle_cell` or resizing: the log then starts again from the
+	/// changed board.
+	pub fn start_recording(&mut self) {
+		self.recording = Some(Recording {
+			start: self.cells.clone(),
+			deltas: VecDeque::new(),
+		});
+	}
+
+	pub fn stop_recording(&mut self) {
+		self.recording = None;
+	}
 
-		let east = if column == self.width - 1 {
-			0
+	/// Caps the recording at `cap` generations. Once full, the oldest delta
+	/// is folded into the starting board, so the log always replays to the
+	/// current board.
+	pub fn set_recording_cap(&mut self, cap: u32) {
+		self.recording_cap = cap;
+		while self.trim_recording() {}
+	}
+
+	pub fn generation(&self) -> u64 {
+		self.generation
+	}
+
+	/// Runs `steps` generations, saving the board every few of them (see
+	/// `set_snapshot_interval`) so `seek` can jump back and forth along the
+	/// run. The board ends up `steps` generations on, and any snapshots
+	/// from an earlier `precompute` are dropped. So is the whole timeline
+	/// once the cells or any setting that shapes the run are changed.
+	pub fn precompute(&mut self, steps: u32) {
+		self.snapshots.clear();
+		self.save_snapshot();
+		let start = self.generation;
+		for _ in 0..steps {
+			self.tick(0);
+			if self.generation == start + self.snapshots.len() as u64 * u64::from(self.snapshot_interval) {
+				self.save_snapshot();
+			}
+		}
+	}
+
+	/// Moves the board to `generation` by restoring the closest snapshot
+	/// at or before it and ticking forward from there; with no suitable
+	/// snapshot it ticks on from the current board instead. Returns whether
+	/// the board got there, which doesn't happen for a generation before
+	/// every snapshot (and the current board), or when a population cap or
+	/// spaceship stop holds the board back on the way.
+	///
+	/// Jumping replaces the board, so a recording is stopped and history
+	/// after the new generation is forgotten.
+	pub fn seek(&mut self, generation: u64) -> bool {
+		let snapshot = self
+			.snapshots
+			.iter()
+			.rev()
+			.find(|snapshot| snapshot.generation <= generation && snapshot.cells.len() == self.cells.len());
+		match snapshot {
+			Some(snapshot) if snapshot.generation > self.generation || generation < self.generation => {
+				self.cells = snapshot.cells.clone();
+				self.generation = snapshot.generation;
+				self.recording = None;
+				self.partial_tick = None;
+				while self.population_history.back().is_some_and(|&(recorded, _)| recorded > self.generation) {
+					self.population_history.pop_back();
+				}
+			}
+			_ if generation < self.generation => return false,
+			_ => {}
+		}
+
+		while self.generation < generation {
+			let before = self.generation;
+			self.tick(0);
+			if self.generation == before {
+				return false;
+			}
+		}
+		true
+	}
+
+	/// Saves a snapshot every `interval` generations in `precompute`. A
+	/// short interval makes `seek` quick, at most `interval - 1` ticks from
+	/// a snapshot, but keeps a whole board per snapshot; a long one saves
+	/// memory and makes seeking slower. Takes effect on the next
+	/// `precompute`.
+	pub fn set_snapshot_interval(&mut self, interval: u32) -> Result<(), LifeError> {
+		if interval == 0 {
+			return Err(LifeError::ZeroArgument("interval"));
+		}
+		self.snapshot_interval = interval;
+		Ok(())
+	}
+
+	/// The rule the next tick uses, which follows `set_rule_schedule` once
+	/// one of its entries has started, in canonical B/S notation: `B`, the
+	/// birth counts in increasing order, `/S` and the survival counts, such
+	/// as `B36/S23` (with counts past 8 written in the comma form `set_rule`
+	/// takes).
+	pub fn rule(&self) -> String {
+		self.scheduled_rule().to_string()
+	}
+
+	/// A label for the kind of automaton the board currently runs, fixed
+	/// so the front-end can match on it:
+	///
+	/// - `"Larger than Life (radius 2, 2-state)"` with a radius 2
+	///   neighbourhood, whatever the weights;
+	/// - `"Weighted"` at radius 1 with any neighbour weight other than 1;
+	/// - `"Life-like (outer-totalistic, 2-state)"` otherwise, for Conway's
+	///   rule and every other B/S rule.
+	///
+	/// Only two-state automata exist here, so there's no label for
+	/// multi-state families such as Generations.
+	pub fn rule_family(&self) -> String {
+		let family = if self.neighborhood_radius > 1 {
+			"Larger than Life (radius 2, 2-state)"
+		} else if self.neighbor_weights.iter().any(|&weight| weight != 1) {
+			"Weighted"
 		} else {
-			column + 1
+			"Life-like (outer-totalistic, 2-state)"
 		};
+		family.to_string()
+	}
 
-		let nw = self.get_index(north, west);
-		count += self.cells[nw] as u8;
+	/// Whether the board runs the rule `expected`, for checking that an
+	/// imported pattern meant for, say, HighLife isn't about to run under
+	/// Conway's rule. `expected` is read like `set_rule` reads it, so case
+	/// and the order of the digits don't matter; the comparison is between
+	/// the canonical forms `rule` returns. An unreadable rule never
+	/// matches.
+	pub fn assert_rule(&self, expected: &str) -> bool {
+		expected.parse::<Rule>().is_ok_and(|expected| expected == self.scheduled_rule())
+	}
 
-		let n = self.get_index(north, column);
-		count += self.cells[n] as u8;
+	/// Switches to the rule given in B/S notation, e.g. `B36/S23` for
+	/// HighLife. An invalid rule leaves the current one in place.
+	pub fn set_rule(&mut self, rule: &str) -> Result<(), LifeError> {
+		self.rule = rule.parse()?;
+		self.settings_changed();
+		Ok(())
+	}
 
-		let ne = self.get_index(north, east);
-		count += self.cells[ne] as u8;
+	/// The population history as CSV: a header row, then one
+	/// `generation,population` row for the board the run started from and
+	/// one per tick, an edit replacing the row of the generation it was
+	/// made at. Only the last `POPULATION_HISTORY_LEN`
+	/// (1024) generations are kept, so older ticks are missing from longer
+	/// runs.
+	pub fn population_csv(&self) -> String {
+		let mut csv = String::from("generation,population\n");
+		for &(generation, population) in &self.population_history {
+			csv.push_str(&format!("{},{}\n", generation, population));
+		}
+		csv
+	}
 
-		let w = self.get_index(row, west);
-		count += self.cells[w] as u8;
+	/// The number of horizontally or vertically adjacent pairs of cells in
+	/// different states, a measure of how broken up the board is (like the
+	/// energy of an Ising model): 0 for a uniform board, highest for a
+	/// checkerboard. Pairs across the edges count only if the next tick
+	/// wraps round, with a `Toroidal` boundary or one `set_wrap_after` has
+	/// switched to.
+	pub fn edge_energy(&self) -> u32 {
+		let wraps = self.scheduled_boundary() == Boundary::Toroidal;
+		let mut energy = 0;
+		for row in 0..self.height {
+			for col in 0..self.width {
+				let cell = self.cells[self.get_index(row, col)];
+				if (col + 1 < self.width || wraps) && self.cells[self.get_index(row, col + 1)] != cell {
+					energy += 1;
+				}
+				if (row + 1 < self.height || wraps) && self.cells[self.get_index(row + 1, col)] != cell {
+					energy += 1;
+				}
+			}
+		}
+		energy
+	}
 
-		let e = self.get_index(row, east);
-		count += self.cells[e] as u8;
+	/// Live cells in each row, top to bottom.
+	pub fn row_populations(&self) -> Vec<u32> {
+		let width = self.width.max(1) as usize;
+		self.cells
+			.chunks(width)
+			.map(|row| row.iter().filter(|&&cell| cell == Cell::Alive).count() as u32)
+			.collect()
+	}
 
-		let sw = self.get_index(south, west);
-		count += self.cells[sw] as u8;
+	/// Live cells in each column, left to right.
+	pub fn col_populations(&self) -> Vec<u32> {
+		let mut populations = vec![0; self.width as usize];
+		for (idx, &cell) in self.cells.iter().enumerate() {
+			if cell == Cell::Alive {
+				populations[idx % self.width as usize] += 1;
+			}
+		}
+		populations
+	}
+	
+	fn get_index(&self, row: u32, column: u32) -> usize {
+        let (_row_normalize, _column_normalize) = self.normalize_coordinate(row, column);
+		(_row_normalize * self.width + _column_normalize) as usize
+    }
+	
+	fn normalize_coordinate(&self, row: u32, col: u32) -> (u32, u32) {
+		(row % self.height, col % self.width)
+	}
+	
+	/// The live neighbours of a cell, each counted with its weight from
+	/// `set_neighbor_weights` (1 apiece by default).
+	fn live_neighbor_count(&self, row: u32, column: u32) -> i32 {
+		self.weighted_count(row, column, self.scheduled_boundary())
+	}
 
-		let s = self.get_index(south, column);
-		count += self.cells[s] as u8;
+	/// The count `tick` compares against the rule. At radius 1 each of the
+	/// eight neighbours adds its weight, saturating rather than overflowing
+	/// so extreme weights just fall outside every rule; wider neighbourhoods
+	/// count every live cell once.
+	fn weighted_count(&self, row: u32, column: u32, boundary: Boundary) -> i32 {
+		if self.neighborhood_radius != 1 {
+			return self.window_count(row, column, boundary, self.neighborhood_radius);
+		}
+		self.neighbors(row, column, boundary)
+			.iter()
+			.zip(&self.neighbor_weights)
+			.fold(0i32, |count, (&cell, &weight)| count.saturating_add(weight * cell as i32))
+	}
 
-		let se = self.get_index(south, east);
-		count += self.cells[se] as u8;
+	fn has_live_neighbor(&self, row: u32, column: u32, boundary: Boundary) -> bool {
+		self.window_count(row, column, boundary, self.neighborhood_radius) > 0
+	}
 
+	/// The live cells, unweighted, in the square of the given radius around
+	/// a cell, not counting the cell itself.
+	fn window_count(&self, row: u32, column: u32, boundary: Boundary, radius: u32) -> i32 {
+		let radius = i64::from(radius);
+		let mut count = 0;
+		for dr in -radius..=radius {
+			for dc in -radius..=radius {
+				if (dr, dc) != (0, 0) && self.cell_at(i64::from(row) + dr, i64::from(column) + dc, boundary) == Cell::Alive {
+					count += 1;
+				}
+			}
+		}
 		count
-    }
+	}
+
+	/// The eight neighbours of a cell, in the north-west to south-east order
+	/// of `neighbor_weights`.
+	fn neighbors(&self, row: u32, column: u32, boundary: Boundary) -> [Cell; 8] {
+		const OFFSETS: [(i64, i64); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+		let mut neighbors = [Cell::Dead; 8];
+		for (neighbor, &(dr, dc)) in neighbors.iter_mut().zip(&OFFSETS) {
+			*neighbor = self.cell_at(i64::from(row) + dr, i64::from(column) + dc, boundary);
+		}
+		neighbors
+	}
+
+	/// The cell at a position that may be past the edges. With a `Dead`
+	/// boundary those are always dead; with `Toroidal` they wrap round to
+	/// the opposite edge.
+	fn cell_at(&self, row: i64, column: i64, boundary: Boundary) -> Cell {
+		let (height, width) = (i64::from(self.height), i64::from(self.width));
+		let inside = (0..height).contains(&row) && (0..width).contains(&column);
+		if inside || boundary == Boundary::Toroidal {
+			self.cells[self.get_index(row.rem_euclid(height) as u32, column.rem_euclid(width) as u32)]
+		} else {
+			Cell::Dead
+		}
+	}
 	
 	pub fn width(&self) -> u32 {
         self.width
     }
 	
+	/// Resizes the board and clears it, starting again from generation 0
+	/// with a population history of just that generation.
 	pub fn set_width(&mut self, width: u32) {
         self.width = width;
         self.cells = (0..width * self.height).map(|_i| Cell::Dead).collect();
+        self.generation = 0;
+        self.population_history.clear();
+        self.cells_edited();
     }
 
     pub fn height(&self) -> u32 {
         self.height
     }
 	
+	/// Resizes the board and clears it, as `set_width` does.
 	 pub fn set_height(&mut self, height: u32) {
         self.height = height;
         self.cells = (0..self.width * height).map(|_i| Cell::Dead).collect();
+        self.generation = 0;
+        self.population_history.clear();
+        self.cells_edited();
     }
 
     pub fn cells(&self) -> *const Cell {
         self.cells.as_ptr()
     }
+
+	/// The live-neighbour count the next `tick` sees for every cell, in the
+	/// same row-major order as `cells`, with any neighbour weights applied
+	/// and the boundary that tick will use.
+	pub fn neighbor_counts(&self) -> Vec<i32> {
+		let boundary = self.scheduled_boundary();
+		(0..self.height)
+			.flat_map(|row| (0..self.width).map(move |col| (row, col)))
+			.map(|(row, col)| self.weighted_count(row, col, boundary))
+			.collect()
+	}
+
+	/// Reads a cell on the torus: coordinates past the right or bottom edge
+	/// wrap around to the opposite side, just as they do for `tick`.
+	pub fn get_cell(&self, row: u32, column: u32) -> Cell {
+		self.cells[self.get_index(row, column)]
+	}
+
+	/// Reads a cell on the finite plane: anything off the board, including
+	/// negative coordinates, is `Cell::Dead` instead of wrapping around.
+	pub fn get_cell_dead(&self, row: i64, column: i64) -> Cell {
+		if row < 0 || column < 0 || row >= self.height as i64 || column >= self.width as i64 {
+			return Cell::Dead;
+		}
+		self.cells[self.get_index(row as u32, column as u32)]
+	}
 	
 	pub fn toggle_cell(&mut self, row: u32, column: u32) {
         let idx = self.get_index(row, column);
         self.cells[idx].toggle();
+        self.cells_edited();
     }
 	
 	pub fn toggle_live_cell(&mut self) {
@@ -255,6 +1185,7 @@ impl Universe {
 				self.cells[idx] = cell 	
 			}
 		}	
+		self.cells_edited();
 	}
 	
 	pub fn create_glider(&mut self, row: u32, col: u32) {
@@ -265,6 +1196,7 @@ impl Universe {
 		self.cells[idx1] = Cell::Alive; 
 		self.cells[idx2] = Cell::Alive; 
 		self.cells[idx3] = Cell::Alive; 
+		self.cells_edited();
 	}
 	
 	pub fn create_pulsar_gerator(&mut self, row: u32, col: u32) {
@@ -300,9 +1232,172 @@ impl Universe {
 		
 		index = self.get_index(row - 1, col - 1);
 		self.cells[index] = Cell::Alive; 
+		self.cells_edited();
+	}
+	
+	/// Stamps a pattern, given as in `Pattern::new`, all over the board:
+	/// its top-left corner goes at `(0, 0)` and then every `spacing_row`
+	/// rows and `spacing_col` columns from there. Copies running off the
+	/// bottom or right edge wrap round with a `Toroidal` boundary and are
+	/// cut off with a `Dead` one. Only live cells are set; nothing is
+	/// cleared.
+	pub fn stamp_grid(&mut self, pattern: &[u8], pattern_width: u32, spacing_row: u32, spacing_col: u32) -> Result<(), LifeError> {
+		if pattern_width == 0 {
+			return Err(LifeError::ZeroArgument("pattern_width"));
+		}
+		if spacing_row == 0 {
+			return Err(LifeError::ZeroArgument("spacing_row"));
+		}
+		if spacing_col == 0 {
+			return Err(LifeError::ZeroArgument("spacing_col"));
+		}
+
+		let pattern = Pattern::new(pattern_width, pattern);
+		let live: Vec<(u32, u32)> = pattern.live_cells().collect();
+		let boundary = self.scheduled_boundary();
+		for top in (0..self.height).step_by(spacing_row as usize) {
+			for left in (0..self.width).step_by(spacing_col as usize) {
+				for &(r, c) in &live {
+					let (row, col) = (top + r, left + c);
+					if boundary == Boundary::Dead && (row >= self.height || col >= self.width) {
+						continue;
+					}
+					let idx = self.get_index(row, col);
+					self.cells[idx] = Cell::Alive;
+				}
+			}
+		}
+		self.cells_edited();
+		Ok(())
+	}
+
+	/// Writes `text` onto the board in a 3x5 cell font, with the top-left
+	/// corner of the first character at `(row, col)` and one blank column
+	/// between characters. Letters (in either case) and digits are drawn;
+	/// anything else leaves a blank space, except `\n`, which starts a new
+	/// line six rows down. Text running off the bottom or right edge wraps
+	/// round with a `Toroidal` boundary and is cut off with a `Dead` one.
+	/// Only cells of the glyphs are set alive; nothing is cleared.
+	pub fn stamp_text(&mut self, text: &str, row: u32, col: u32) {
+		let boundary = self.scheduled_boundary();
+		let (mut line_row, mut glyph_col) = (row, col);
+		for c in text.chars() {
+			if c == '\n' {
+				line_row += font::GLYPH_HEIGHT + 1;
+				glyph_col = col;
+				continue;
+			}
+			if let Some(rows) = font::glyph(c) {
+				for (r, bits) in rows.iter().enumerate() {
+					for dc in 0..font::GLYPH_WIDTH {
+						if bits & (1 << (font::GLYPH_WIDTH - 1 - dc)) == 0 {
+							continue;
+						}
+						let (row, col) = (line_row + r as u32, glyph_col + dc);
+						if boundary == Boundary::Dead && (row >= self.height || col >= self.width) {
+							continue;
+						}
+						let idx = self.get_index(row, col);
+						self.cells[idx] = Cell::Alive;
+					}
+				}
+			}
+			glyph_col += font::GLYPH_WIDTH + 1;
+		}
+		self.cells_edited();
+	}
+
+	/// Sets the live cells of `pattern` alive with the top-left corner of
+	/// the placed pattern at `(row, col)`, wrapping around the edges.
+	///
+	/// The pattern is first mirrored, left to right with `flip_h` and top to
+	/// bottom with `flip_v`, then turned `rotation` quarter turns clockwise.
+	/// Flipping a glider along one axis reverses that part of its heading.
+	pub fn insert_pattern(&mut self, pattern: &Pattern, row: u32, col: u32, rotation: u32, flip_h: bool, flip_v: bool) {
+		let placed = pattern.transformed(rotation, flip_h, flip_v);
+		for (r, c) in placed.live_cells() {
+			let idx = self.get_index(row + r, col + c);
+			self.cells[idx] = Cell::Alive;
+		}
+		self.cells_edited();
+	}
+
+	/// Which of the 8 symmetries of the square the live cells, cropped to
+	/// their bounding box, are unchanged by, as a bitmask.
+	///
+	/// Bit `k` for `k` in `0..4` is a turn of `k` quarter turns clockwise,
+	/// with bit 0 the identity. Bit `4 + k` is a left-to-right mirror
+	/// followed by the same turn: bit 4 mirrors left to right, bit 5 along
+	/// the anti-diagonal, bit 6 top to bottom and bit 7 along the main
+	/// diagonal. An empty board has no pattern to compare and returns 0.
+	pub fn detect_symmetries(&self) -> u8 {
+		let cropped = match self.crop() {
+			Some(cropped) => cropped,
+			None => return 0,
+		};
+
+		let mut symmetries = 0;
+		for (bit, &flip_h) in [false, true].iter().enumerate() {
+			for rotation in 0..4 {
+				if cropped.transformed(rotation, flip_h, false) == cropped {
+					symmetries |= 1 << (bit as u32 * 4 + rotation);
+				}
+			}
+		}
+		symmetries
+	}
+
+	/// How many ways the live cells' bounding box can be turned onto
+	/// itself: 4 if a quarter turn leaves it unchanged, 2 if only a half
+	/// turn does, and 1 otherwise, including for an empty board.
+	pub fn rotational_order(&self) -> u8 {
+		let cropped = match self.crop() {
+			Some(cropped) => cropped,
+			None => return 1,
+		};
+
+		if cropped.transformed(1, false, false) == cropped {
+			4
+		} else if cropped.transformed(2, false, false) == cropped {
+			2
+		} else {
+			1
+		}
+	}
+	
+	/// Replaces the board with a logical AND gate made of glider streams.
+	///
+	/// Gun A and gun B fire the two inputs towards the south-east, while gun C
+	/// fires south-west across both lanes. Every glider from B annihilates the
+	/// matching glider from C, so C only survives where B is missing, and a
+	/// surviving C glider would in turn annihilate the A glider it crosses.
+	/// The A stream therefore reaches the eater at the bottom only when both
+	/// inputs are present: A AND B. With both guns running, as here, the
+	/// output stream is full and the scene settles into a period of 30.
+	pub fn setup_and_gate(&mut self) {
+		self.set_width(AND_GATE_WIDTH);
+		self.set_height(AND_GATE_HEIGHT);
+		
+		let gun = Pattern::from_positions(&GOSPER_GLIDER_GUN);
+		let eater = Pattern::from_positions(&EATER);
+
+		// Input A
+		self.insert_pattern(&gun, 1, 0, 0, false, false);
+		// Input B
+		self.insert_pattern(&gun, 1, 40, 0, false, false);
+		// Inverter for B, mirrored so it fires south-west
+		self.insert_pattern(&gun, 0, 93, 0, true, false);
+		// Output
+		self.insert_pattern(&eater, 91, 104, 0, false, false);
 	}
 }
 
+impl Default for Universe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Universe {
     /// Get the dead and alive values of the entire universe.
     pub fn get_cells(&self) -> &[Cell] {
@@ -316,8 +1411,580 @@ impl Universe {
             let idx = self.get_index(row, col);
             self.cells[idx] = Cell::Alive;
         }
+        self.cells_edited();
+    }
+
+    /// Estimate the `(width, height)` a board needs so that `pattern` can run
+    /// for `generations` without touching itself across the wrapped edges.
+    ///
+    /// The pattern is simulated on a board wide enough that nothing can wrap
+    /// (cells spread at most one step per generation), and the result is the
+    /// size of every cell it lit up along the way plus `MIN_GRID_MARGIN` on
+    /// each side. It's an upper estimate: debris that has already died still
+    /// counts towards the area.
+    pub fn min_grid_for(pattern: Pattern, generations: u32) -> (u32, u32) {
+        let reach = generations + 1;
+        let mut universe =
+            Universe::dead(pattern.width + 2 * reach, pattern.height + 2 * reach);
+        universe.insert_pattern(&pattern, reach, reach, 0, false, false);
+
+        let mut extent = universe.bounding_box();
+        for _ in 0..generations {
+            universe.step();
+            extent = match (extent, universe.bounding_box()) {
+                (Some(a), Some(b)) => Some((a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))),
+                (a, b) => a.or(b),
+            };
+        }
+
+        match extent {
+            Some((min_row, min_col, max_row, max_col)) => (
+                max_col - min_col + 1 + 2 * MIN_GRID_MARGIN,
+                max_row - min_row + 1 + 2 * MIN_GRID_MARGIN,
+            ),
+            None => (2 * MIN_GRID_MARGIN, 2 * MIN_GRID_MARGIN),
+        }
     }
 
+    /// The smallest square torus, between `min` and `max` cells a side, on
+    /// which `pattern` runs for `generations` exactly as it would on an
+    /// endless plane under Conway's rule, so nothing it sends out wraps
+    /// round and runs into the rest of it. `None` if no size in the range
+    /// is clean; sizes too small to hold the pattern are skipped.
+    ///
+    /// Every size is simulated in full, so the cost is bounded by about
+    /// `(max - min + 1) * generations * max * max` cell updates.
+    pub fn find_clean_torus_size(pattern: Pattern, min: u32, max: u32, generations: u32) -> Option<(u32, u32)> {
+        let reach = generations + 1;
+        let mut plane = Universe::dead(pattern.width + 2 * reach, pattern.height + 2 * reach);
+        plane.insert_pattern(&pattern, reach, reach, 0, false, false);
+
+        // Live cells of every generation on the plane, relative to where
+        // the pattern was placed.
+        let mut history = Vec::with_capacity(generations as usize + 1);
+        for generation in 0..=generations {
+            if generation > 0 {
+                plane.step();
+            }
+            let width = plane.width;
+            let live: Vec<(i64, i64)> = plane
+                .cells
+                .iter()
+                .enumerate()
+                .filter(|&(_, &cell)| cell == Cell::Alive)
+                .map(|(idx, _)| {
+                    let (row, col) = (idx as u32 / width, idx as u32 % width);
+                    (i64::from(row) - i64::from(reach), i64::from(col) - i64::from(reach))
+                })
+                .collect();
+            history.push(live);
+        }
+
+        let smallest = min.max(pattern.width).max(pattern.height).max(1);
+        (smallest..=max)
+            .find(|&size| {
+                let mut torus = Universe::dead(size, size);
+                torus.insert_pattern(&pattern, 0, 0, 0, false, false);
+                history.iter().enumerate().all(|(generation, live)| {
+                    if generation > 0 {
+                        torus.step();
+                    }
+                    let mut folded = vec![Cell::Dead; torus.cells.len()];
+                    for &(row, col) in live {
+                        let (row, col) = (row.rem_euclid(i64::from(size)), col.rem_euclid(i64::from(size)));
+                        folded[(row * i64::from(size) + col) as usize] = Cell::Alive;
+                    }
+                    folded == torus.cells
+                })
+            })
+            .map(|size| (size, size))
+    }
+
+    fn save_snapshot(&mut self) {
+        self.snapshots.push(Snapshot {
+            generation: self.generation,
+            cells: self.cells.clone(),
+        });
+    }
+
+    /// The recorded board changes, one list of flipped cell indices per
+    /// generation, oldest first. Empty when nothing is being recorded.
+    pub fn replay(&self) -> Vec<Vec<u32>> {
+        match &self.recording {
+            Some(recording) => recording.deltas.iter().cloned().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// The board the recorded deltas in `replay` start from, if recording.
+    pub fn recording_start(&self) -> Option<&[Cell]> {
+        self.recording.as_ref().map(|recording| recording.start.as_slice())
+    }
+
+    /// The board as it was `lag` generations ago, undone from the recorded
+    /// deltas, or `None` if fewer than `lag` generations were recorded.
+    fn recorded_past(&self, lag: u32) -> Option<Vec<Cell>> {
+        let recording = self.recording.as_ref()?;
+        if recording.deltas.len() < lag as usize || recording.start.len() != self.cells.len() {
+            return None;
+        }
+        let mut past = self.cells.clone();
+        for delta in recording.deltas.iter().rev().take(lag as usize) {
+            for &idx in delta {
+                past[idx as usize].toggle();
+            }
+        }
+        Some(past)
+    }
+
+    /// Write the `render_frames_rgba` header, `frame_count` frames of this
+    /// board at `cell_px` pixels per cell, to the start of `frames`.
+    fn push_frames_header(&self, frames: &mut Vec<u8>, frame_count: u32, cell_px: u32) {
+        frames.extend_from_slice(&frame_count.to_le_bytes());
+        frames.extend_from_slice(&(self.width * cell_px).to_le_bytes());
+        frames.extend_from_slice(&(self.height * cell_px).to_le_bytes());
+    }
+
+    /// The board drawn `cell_px` pixels square per cell into an RGBA buffer
+    /// in row-major order, each cell in the `0xRRGGBBAA` colour `color_of`
+    /// picks for its flat index.
+    fn rgba_image(&self, cell_px: u32, color_of: impl Fn(usize) -> u32) -> Vec<u8> {
+        let (cell_px, width) = (cell_px as usize, self.width as usize);
+        let mut pixels = Vec::with_capacity(self.cells.len() * cell_px * cell_px * 4);
+        for row in 0..self.height as usize {
+            let line: Vec<u8> = (0..width)
+                .flat_map(|col| {
+                    let color = color_of(row * width + col).to_be_bytes();
+                    std::iter::repeat_n(color, cell_px).flatten()
+                })
+                .collect();
+            for _ in 0..cell_px {
+                pixels.extend_from_slice(&line);
+            }
+        }
+        pixels
+    }
+
+    /// Log the cells that differ between the current board and `next`.
+    fn record_delta(&mut self, next: &[Cell]) {
+        if let Some(recording) = &mut self.recording {
+            let delta = self
+                .cells
+                .iter()
+                .zip(next)
+                .enumerate()
+                .filter(|&(_, (before, after))| before != after)
+                .map(|(idx, _)| idx as u32)
+                .collect();
+            recording.deltas.push_back(delta);
+            self.perf.count_allocation();
+        }
+        self.trim_recording();
+    }
+
+    /// Fold the oldest delta into the starting board if the log is over its
+    /// cap. Returns whether anything was folded.
+    fn trim_recording(&mut self) -> bool {
+        let cap = self.recording_cap as usize;
+        match &mut self.recording {
+            Some(recording) if recording.deltas.len() > cap => {
+                if let Some(delta) = recording.deltas.pop_front() {
+                    for idx in delta {
+                        recording.start[idx as usize].toggle();
+                    }
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The `(row, col)` centre of mass of the live cells, averaged around
+    /// the torus so a pattern straddling an edge is centred on the edge, or
+    /// `None` for an empty board.
+    fn center_of_mass(&self) -> Option<(f64, f64)> {
+        use std::f64::consts::TAU;
+
+        if self.live_count() == 0 {
+            return None;
+        }
+
+        let (mut row_sin, mut row_cos, mut col_sin, mut col_cos) = (0.0, 0.0, 0.0, 0.0);
+        for (idx, &cell) in self.cells.iter().enumerate() {
+            if cell == Cell::Dead {
+                continue;
+            }
+            let row_angle = TAU * (idx as u32 / self.width) as f64 / self.height as f64;
+            let col_angle = TAU * (idx as u32 % self.width) as f64 / self.width as f64;
+            row_sin += row_angle.sin();
+            row_cos += row_angle.cos();
+            col_sin += col_angle.sin();
+            col_cos += col_angle.cos();
+        }
+
+        let mean = |sin: f64, cos: f64, size: u32| -> f64 {
+            (sin.atan2(cos) / TAU).rem_euclid(1.0) * size as f64
+        };
+        Some((mean(row_sin, row_cos, self.height), mean(col_sin, col_cos, self.width)))
+    }
+
+    /// Switch rules during `tick` as the board reaches given generations.
+    ///
+    /// Each entry pairs the generation a rule takes effect at with the rule
+    /// in B/S notation, so `[(0, "B3/S23"), (500, "B36/S23")]` plays Conway
+    /// and turns into HighLife at generation 500. Every tick uses the entry
+    /// with the latest start not after the current generation, overriding
+    /// the rule from `set_rule`, which still applies before the first entry
+    /// starts. All rules are validated first; if any is invalid the
+    /// schedule is left unchanged.
+    pub fn set_rule_schedule(&mut self, schedule: Vec<(u64, String)>) -> Result<(), LifeError> {
+        let mut parsed = schedule
+            .iter()
+            .map(|(generation, rule)| Ok((*generation, rule.parse()?)))
+            .collect::<Result<Vec<(u64, Rule)>, LifeError>>()?;
+        parsed.sort_by_key(|&(generation, _)| generation);
+
+        self.rule_schedule = parsed;
+        self.settings_changed();
+        Ok(())
+    }
+
+    /// The set-up `tick` does before computing any cells. Returns `false`
+    /// if the board is stopped and no generation should be computed.
+    fn begin_generation(&mut self) -> bool {
+        self.dirty_rect = None;
+        !(self.stop_when_only_spaceships && self.only_spaceships_left())
+    }
+
+    /// A `tick(0)` without the timers, for the generations the board runs
+    /// on its own account: the spaceship check inside a tick and every
+    /// scratch simulation. These would otherwise nest a second
+    /// `Universe::tick` timer inside the one running, or flood the console
+    /// with timings nobody asked for.
+    fn step(&mut self) {
+        self.partial_tick = None;
+        if !self.begin_generation() {
+            return;
+        }
+        self.perf.count_allocation();
+        let next = self.next_generation(self.cells.clone(), 0);
+        self.finish_generation(next);
+    }
+
+    /// Fills `next`, a copy of the board, with the next generation of every
+    /// cell from `range` on in both directions.
+    fn next_generation(&mut self, mut next: Vec<Cell>, range: u32) -> Vec<Cell> {
+        let rule = self.scheduled_rule();
+        for row in range..self.height {
+            for col in range..self.width {
+                let idx = self.get_index(row, col);
+                next[idx] = self.next_cell(row, col, rule);
+            }
+        }
+        let side = |length: u32| length.saturating_sub(range) as usize;
+        self.perf.count_cells(side(self.height) * side(self.width));
+        next
+    }
+
+    /// The state of the cell at `row`, `col` in the next generation under
+    /// `rule`.
+    fn next_cell(&self, row: u32, col: u32, rule: Rule) -> Cell {
+        let cell = self.cells[self.get_index(row, col)];
+        let live_neighbors = self.live_neighbor_count(row, col);
+
+        // Birth and survival follow the active rule, Conway's B3/S23 unless
+        // another one was set or scheduled.
+        if rule.next_state(cell == Cell::Alive, live_neighbors) {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+
+    /// Replaces the board with the fully computed `next` generation, unless
+    /// it would go over the population cap. Returns whichever generation
+    /// was left unused, for `tick` to time freeing it.
+    fn finish_generation(&mut self, next: Vec<Cell>) -> Vec<Cell> {
+        self.perf.count_generation();
+        if let Some(cap) = self.max_population {
+            let population = next.iter().filter(|&&cell| cell == Cell::Alive).count() as u32;
+            self.population_cap_hit = population > cap;
+            if self.population_cap_hit {
+                return next;
+            }
+        }
+
+        self.record_delta(&next);
+        let changed = self.cells.iter().zip(&next).enumerate().filter(|&(_, (before, after))| before != after);
+        self.dirty_rect = self.extent(changed.map(|(idx, _)| idx));
+
+        // Boards built cell by cell haven't logged where they started.
+        if self.population_history.is_empty() {
+            self.record_population();
+        }
+        let old = std::mem::replace(&mut self.cells, next);
+        self.generation += 1;
+        self.record_population();
+        old
+    }
+
+    /// The boundary the next `tick` will use, following `set_wrap_after`
+    /// if it was called.
+    fn scheduled_boundary(&self) -> Boundary {
+        match self.wrap_after {
+            Some(start) if self.generation >= start => Boundary::Toroidal,
+            Some(_) => Boundary::Dead,
+            None => self.boundary,
+        }
+    }
+
+    /// The rule the next `tick` will use: the latest schedule entry that
+    /// has started, or the rule from `set_rule` if none has. It's worked
+    /// out from the generation every time, so going back with `seek` or a
+    /// reset picks the earlier rule up again.
+    fn scheduled_rule(&self) -> Rule {
+        let generation = self.generation;
+        self.rule_schedule
+            .iter()
+            .rev()
+            .find(|&&(start, _)| start <= generation)
+            .map_or(self.rule, |&(_, rule)| rule)
+    }
+
+    /// Push the current population onto the history, dropping the oldest
+    /// entry once it holds `POPULATION_HISTORY_LEN` generations. An entry
+    /// already there for this generation, from before an edit, is replaced.
+    fn record_population(&mut self) {
+        if self.population_history.back().is_some_and(|&(recorded, _)| recorded == self.generation) {
+            self.population_history.pop_back();
+        }
+        if self.population_history.len() == POPULATION_HISTORY_LEN {
+            self.population_history.pop_front();
+        }
+        self.population_history.push_back((self.generation, self.live_count()));
+    }
+
+    /// Number of live cells on the board.
+    fn live_count(&self) -> u32 {
+        self.cells.iter().filter(|&&cell| cell == Cell::Alive).count() as u32
+    }
+
+    /// A hash of the cells alone, for spotting repeated boards cheaply.
+    fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.cells.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Weigh each neighbour's contribution to the count the rule sees, in
+    /// the order NW, N, NE, W, E, SW, S, SE (the neighbours in reading
+    /// order). The weighted total is compared against the rule's birth and
+    /// survival counts, so uneven weights give asymmetric automata; totals
+    /// outside 0 to 31 never match. All ones is standard Life.
+    pub fn set_neighbor_weights(&mut self, weights: [i32; 8]) {
+        self.neighbor_weights = weights;
+        self.settings_changed();
+    }
+
+    /// An empty board of the given size that evolves under the same rule,
+    /// boundary and neighbourhood as this one.
+    fn dead_with_rules(&self, width: u32, height: u32) -> Universe {
+        let mut universe = Universe::dead(width, height);
+        universe.rule = self.scheduled_rule();
+        universe.neighbor_weights = self.neighbor_weights;
+        universe.boundary = self.scheduled_boundary();
+        universe.neighborhood_radius = self.neighborhood_radius;
+        universe
+    }
+
+    /// Throws away work done under the board's old settings: a generation
+    /// `tick_budget` has only partly computed, and the `precompute`
+    /// snapshots, which no longer lie on the board's future.
+    fn settings_changed(&mut self) {
+        self.partial_tick = None;
+        self.snapshots.clear();
+    }
+
+    /// Like `settings_changed`, for a change to the cells made other than
+    /// by a tick. A recording only logs what ticks flip, so it starts over
+    /// from the edited board rather than replaying into the wrong one, and
+    /// the population history takes the edited board's count.
+    fn cells_edited(&mut self) {
+        self.settings_changed();
+        if let Some(recording) = &mut self.recording {
+            recording.start = self.cells.clone();
+            recording.deltas.clear();
+        }
+        self.record_population();
+    }
+
+    /// A copy to simulate ahead on, without this board's recording or
+    /// `precompute` snapshots.
+    fn scratch(&self) -> Universe {
+        let mut scratch = self.clone();
+        scratch.recording = None;
+        scratch.snapshots.clear();
+        scratch
+    }
+
+    /// An all-dead universe of the given size, with a random stream of its
+    /// own so no two boards restart into the same soup.
+    fn dead(width: u32, height: u32) -> Universe {
+        Universe {
+            width,
+            height,
+            cells: vec![Cell::Dead; (width * height) as usize],
+            generation: 0,
+            population_history: VecDeque::new(),
+            rule: Rule::default(),
+            rule_schedule: Vec::new(),
+            recording: None,
+            recording_cap: DEFAULT_RECORDING_CAP,
+            stop_when_only_spaceships: false,
+            max_population: None,
+            population_cap_hit: false,
+            neighbor_weights: [1; 8],
+            dirty_rect: None,
+            partial_tick: None,
+            boundary: Boundary::Toroidal,
+            wrap_after: None,
+            neighborhood_radius: 1,
+            rng: Rng::new(random_seed()),
+            snapshots: Vec::new(),
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            perf: PerfCounters::default(),
+        }
+    }
+
+    /// The live cells cut down to their bounding box, or `None` when the
+    /// board is empty.
+    fn crop(&self) -> Option<Pattern> {
+        let (min_row, min_col, max_row, max_col) = self.bounding_box()?;
+        let (width, height) = (max_col - min_col + 1, max_row - min_row + 1);
+        let cells = (0..height)
+            .flat_map(|r| (0..width).map(move |c| (r, c)))
+            .map(|(r, c)| self.cells[self.get_index(min_row + r, min_col + c)])
+            .collect();
+
+        Some(Pattern { width, height, cells })
+    }
+
+    /// The smallest `(min_row, min_col, max_row, max_col)` rectangle holding
+    /// every live cell, or `None` when the board is empty.
+    fn bounding_box(&self) -> Option<(u32, u32, u32, u32)> {
+        let live = self
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|&(_, &cell)| cell == Cell::Alive)
+            .map(|(idx, _)| idx);
+        self.extent(live)
+    }
+
+    /// The tightest rectangle around the given flat indices.
+    fn extent(&self, indices: impl Iterator<Item = usize>) -> Option<(u32, u32, u32, u32)> {
+        let mut extent: Option<(u32, u32, u32, u32)> = None;
+        for idx in indices {
+            let (row, col) = (idx as u32 / self.width, idx as u32 % self.width);
+            extent = Some(match extent {
+                Some((r0, c0, r1, c1)) => (r0.min(row), c0.min(col), r1.max(row), c1.max(col)),
+                None => (row, col, row, col),
+            });
+        }
+        extent
+    }
+}
+
+// Colour of a cell in `Universe::crossfade`.
+fn cell_rgba(cell: Cell) -> [u8; 4] {
+    match cell {
+        Cell::Alive => [0, 0, 0, 255],
+        Cell::Dead => [255, 255, 255, 255],
+    }
+}
+
+// Bytes before the first frame in `Universe::render_frames_rgba`.
+const FRAMES_HEADER_LEN: usize = 12;
+
+// Empty border added on each side by `Universe::min_grid_for`.
+const MIN_GRID_MARGIN: u32 = 2;
+
+/// A rectangular block of cells that can be placed onto a `Universe`.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    width: u32,
+    height: u32,
+    cells: Vec<Cell>,
+}
+
+#[wasm_bindgen]
+impl Pattern {
+    /// Builds a pattern from row-major bytes, `width` to a row, where any
+    /// non-zero byte is a live cell. A short last row is padded with dead
+    /// cells.
+    pub fn new(width: u32, cells: &[u8]) -> Pattern {
+        if width == 0 {
+            return Pattern { width: 0, height: 0, cells: Vec::new() };
+        }
+        let height = (cells.len() as u32).div_ceil(width);
+        let cells = (0..(width * height) as usize)
+            .map(|i| match cells.get(i) {
+                Some(&byte) if byte != 0 => Cell::Alive,
+                _ => Cell::Dead,
+            })
+            .collect();
+
+        Pattern { width, height, cells }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+impl Pattern {
+    /// A copy mirrored left to right with `flip_h` and top to bottom with
+    /// `flip_v`, then turned `rotation` quarter turns clockwise.
+    fn transformed(&self, rotation: u32, flip_h: bool, flip_v: bool) -> Pattern {
+        let turns = rotation % 4;
+        let (width, height) = if turns % 2 == 1 {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        };
+
+        let mut cells = vec![Cell::Dead; self.cells.len()];
+        for (r, c) in self.live_cells() {
+            let mut r = if flip_v { self.height - 1 - r } else { r };
+            let mut c = if flip_h { self.width - 1 - c } else { c };
+            let (mut turned_width, mut turned_height) = (self.width, self.height);
+            for _ in 0..turns {
+                // A clockwise turn sends the left column to the top row.
+                let turned = (c, turned_height - 1 - r);
+                r = turned.0;
+                c = turned.1;
+                std::mem::swap(&mut turned_width, &mut turned_height);
+            }
+            cells[(r * width + c) as usize] = Cell::Alive;
+        }
+
+        Pattern { width, height, cells }
+    }
+
+    /// The `(row, col)` of every live cell, relative to the top-left corner.
+    fn live_cells(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|&(_, &cell)| cell == Cell::Alive)
+            .map(move |(i, _)| (i as u32 / width, i as u32 % width))
+    }
 }
 
 impl Cell {
@@ -327,6 +1994,14 @@ impl Cell {
             Cell::Alive => Cell::Dead,
         };
     }
+
+    /// Pick `alive` or `dead` for this cell.
+    fn rgba(self, alive: u32, dead: u32) -> u32 {
+        match self {
+            Cell::Alive => alive,
+            Cell::Dead => dead,
+        }
+    }
 }
 
 use std::fmt;
@@ -338,7 +2013,7 @@ impl fmt::Display for Universe {
                 let symbol = if cell == Cell::Dead { '◻' } else { '◼' };
                 write!(f, "{}", symbol)?;
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
 
         Ok(())
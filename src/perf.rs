@@ -0,0 +1,81 @@
+//! Work counters for profiling `tick`, so that optimisations can be checked
+//! to actually skip the work they are meant to.
+
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+/// Running totals of the work `tick` and `tick_budget` have done since the
+/// counters were last reset.
+///
+/// Counting only happens when the crate is built with the `analysis`
+/// feature; without it every total stays 0 and ticking costs nothing extra.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PerfCounters {
+    generations: u64,
+    cells_processed: u64,
+    tiles_skipped: u64,
+    allocations: u64,
+}
+
+#[wasm_bindgen]
+impl PerfCounters {
+    /// Generations computed, including ones thrown away for going over the
+    /// population cap. Divide the other totals by this for per-tick figures.
+    pub fn generations(&self) -> u64 {
+        self.generations
+    }
+
+    /// Cells whose next state was worked out from their neighbours.
+    pub fn cells_processed(&self) -> u64 {
+        self.cells_processed
+    }
+
+    /// Tiles left out because nothing in or around them could change. Every
+    /// tick currently visits every cell, so this stays 0 until a sparse
+    /// tick exists to skip them.
+    pub fn tiles_skipped(&self) -> u64 {
+        self.tiles_skipped
+    }
+
+    /// Heap buffers allocated while ticking: the next generation, plus each
+    /// delta logged while recording.
+    pub fn allocations(&self) -> u64 {
+        self.allocations
+    }
+}
+
+impl PerfCounters {
+    pub(crate) fn count_generation(&mut self) {
+        if cfg!(feature = "analysis") {
+            self.generations += 1;
+        }
+    }
+
+    pub(crate) fn count_cells(&mut self, cells: usize) {
+        if cfg!(feature = "analysis") {
+            self.cells_processed += cells as u64;
+        }
+    }
+
+    pub(crate) fn count_allocation(&mut self) {
+        if cfg!(feature = "analysis") {
+            self.allocations += 1;
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// The work counters so far. All 0 unless the crate was built with the
+    /// `analysis` feature.
+    pub fn perf_counters(&self) -> PerfCounters {
+        self.perf
+    }
+
+    /// Starts the work counters again from 0.
+    pub fn reset_perf_counters(&mut self) {
+        self.perf = PerfCounters::default();
+    }
+}
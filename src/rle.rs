@@ -0,0 +1,198 @@
+//! Incremental reader for the run-length encoded (RLE) pattern format.
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::LifeError;
+use crate::rule::Rule;
+use crate::Universe;
+
+// Where the parser is within the file.
+enum Section {
+    /// Before the header, at the start of a line.
+    LineStart,
+    /// Inside a `#` comment line before the header.
+    Comment,
+    /// Inside the `x = .., y = ..` header line, collected so far.
+    Header(String),
+    /// Inside the run-length encoded cells.
+    Body,
+    /// Past the closing `!`; anything after it is ignored.
+    Done,
+}
+
+/// Builds a `Universe` from an RLE file handed over in pieces, so a large
+/// pattern can be parsed while it's still downloading.
+///
+/// Chunks can be split anywhere, even in the middle of a run count or the
+/// header. Errors are remembered and reported by `finish`.
+#[wasm_bindgen]
+pub struct RleParser {
+    section: Section,
+    width: u32,
+    height: u32,
+    rule: Rule,
+    run: Option<u32>,
+    row: u32,
+    col: u32,
+    cells: Vec<(u32, u32)>,
+    error: Option<LifeError>,
+}
+
+#[wasm_bindgen]
+impl RleParser {
+    pub fn new() -> RleParser {
+        RleParser {
+            section: Section::LineStart,
+            width: 0,
+            height: 0,
+            rule: Rule::default(),
+            run: None,
+            row: 0,
+            col: 0,
+            cells: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Parses the next piece of the file.
+    pub fn feed(&mut self, chunk: &str) {
+        for c in chunk.chars() {
+            if self.error.is_some() {
+                return;
+            }
+            if let Err(error) = self.push(c) {
+                self.error = Some(error);
+            }
+        }
+    }
+
+    /// The board described by everything fed so far, sized by the header
+    /// and using its `rule` if it has one.
+    pub fn finish(self) -> Result<Universe, LifeError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        match self.section {
+            Section::Done => {}
+            Section::Body => return Err(invalid("missing `!` at the end")),
+            _ => return Err(invalid("missing `x = .., y = ..` header")),
+        }
+
+        let mut universe = Universe::dead(self.width, self.height);
+        universe.rule = self.rule;
+        universe.set_cells(&self.cells);
+        Ok(universe)
+    }
+}
+
+impl Default for RleParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RleParser {
+    fn push(&mut self, c: char) -> Result<(), LifeError> {
+        match &mut self.section {
+            Section::LineStart => match c {
+                '#' => self.section = Section::Comment,
+                c if c.is_whitespace() => {}
+                c => self.section = Section::Header(c.to_string()),
+            },
+            Section::Comment => {
+                if c == '\n' {
+                    self.section = Section::LineStart;
+                }
+            }
+            Section::Header(line) => {
+                if c == '\n' {
+                    let line = std::mem::take(line);
+                    self.parse_header(&line)?;
+                    self.section = Section::Body;
+                } else {
+                    line.push(c);
+                }
+            }
+            Section::Body => self.push_body(c)?,
+            Section::Done => {}
+        }
+        Ok(())
+    }
+
+    fn parse_header(&mut self, line: &str) -> Result<(), LifeError> {
+        let (mut width, mut height) = (None, None);
+        for field in line.split(',') {
+            let mut parts = field.splitn(2, '=');
+            let key = parts.next().unwrap_or("").trim();
+            let value = parts.next().map(str::trim).ok_or_else(|| invalid("malformed header"))?;
+            match key {
+                "x" => width = value.parse::<u32>().ok(),
+                "y" => height = value.parse::<u32>().ok(),
+                "rule" => self.rule = value.parse()?,
+                _ => {}
+            }
+        }
+
+        match (width, height) {
+            (Some(0), _) | (_, Some(0)) => Err(LifeError::EmptyBoard),
+            (Some(width), Some(height)) if width.checked_mul(height).is_none() => {
+                Err(invalid("`x` times `y` is too many cells"))
+            }
+            (Some(width), Some(height)) => {
+                self.width = width;
+                self.height = height;
+                Ok(())
+            }
+            _ => Err(invalid("header needs numeric `x` and `y`")),
+        }
+    }
+
+    fn push_body(&mut self, c: char) -> Result<(), LifeError> {
+        if let Some(digit) = c.to_digit(10) {
+            let run = self.run.unwrap_or(0);
+            self.run = Some(
+                run.checked_mul(10)
+                    .and_then(|run| run.checked_add(digit))
+                    .ok_or_else(|| invalid("run count too large"))?,
+            );
+            return Ok(());
+        }
+        if c.is_whitespace() {
+            return Ok(());
+        }
+
+        let run = self.run.take().unwrap_or(1);
+        match c {
+            'b' => self.col = self.col.saturating_add(run),
+            'o' => {
+                if self.row >= self.height || self.col.saturating_add(run) > self.width {
+                    return Err(invalid("cells outside the header's size"));
+                }
+                let (row, col) = (self.row, self.col);
+                self.cells.extend((0..run).map(|i| (row, col + i)));
+                self.col += run;
+            }
+            '$' => {
+                self.row = self.row.saturating_add(run);
+                self.col = 0;
+            }
+            '!' => self.section = Section::Done,
+            c => return Err(LifeError::InvalidRle(format!("unexpected {:?}", c))),
+        }
+        Ok(())
+    }
+}
+
+fn invalid(reason: &str) -> LifeError {
+    LifeError::InvalidRle(reason.to_string())
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Reads a whole RLE file at once; see `RleParser` for streaming.
+    pub fn from_rle(text: &str) -> Result<Universe, LifeError> {
+        let mut parser = RleParser::new();
+        parser.feed(text);
+        parser.finish()
+    }
+}
@@ -0,0 +1,44 @@
+/// A small deterministic pseudo-random generator (xorshift64*), so seeded
+/// runs come out the same every time on every platform.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// A generator started from `seed`. Any seed is fine, including 0.
+    pub(crate) fn new(seed: u64) -> Rng {
+        // Run the seed through splitmix64 so nearby seeds give unrelated
+        // streams and the xorshift state is never zero.
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        Rng::from_state(z)
+    }
+
+    /// A generator with exactly this internal state, except that 0, which
+    /// xorshift can't leave, is swapped for a fixed non-zero state.
+    pub(crate) fn from_state(state: u64) -> Rng {
+        Rng {
+            state: if state == 0 { 0x9E37_79B9_7F4A_7C15 } else { state },
+        }
+    }
+
+    /// The internal state, to pick the stream up later with `from_state`.
+    pub(crate) fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// A float uniformly distributed in `[0, 1)`.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::LifeError;
+
+/// A Life-like rule: which live-neighbour counts give birth to a dead cell
+/// and which let a live cell survive. Bit `n` of each mask stands for a
+/// count of `n`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Rule {
+    birth: u32,
+    survival: u32,
+}
+
+impl Rule {
+    /// Conway's Game of Life, B3/S23.
+    pub(crate) const CONWAY: Rule = Rule {
+        birth: 1 << 3,
+        survival: (1 << 2) | (1 << 3),
+    };
+
+    /// Whether a cell in state `alive` with `live_neighbors` live
+    /// neighbours is alive in the next generation. Counts outside the
+    /// masks' 0 to 31 range, which weighted neighbours can produce, never
+    /// match.
+    pub(crate) fn next_state(&self, alive: bool, live_neighbors: i32) -> bool {
+        let mask = if alive { self.survival } else { self.birth };
+        (0..32).contains(&live_neighbors) && mask & (1 << live_neighbors) != 0
+    }
+
+    /// Whether a dead cell with no live neighbours at all is born, which
+    /// brings a whole empty board to life in one tick.
+    pub(crate) fn births_on_zero(&self) -> bool {
+        self.birth & 1 != 0
+    }
+
+    /// The rule born on any of the `birth` counts and surviving on any of
+    /// the `survival` ones, each below 32.
+    pub(crate) fn from_counts(birth: &[u32], survival: &[u32]) -> Result<Rule, LifeError> {
+        let mask = |counts: &[u32]| {
+            counts.iter().try_fold(0, |mask, &n| if n < 32 { Some(mask | 1 << n) } else { None })
+        };
+        match (mask(birth), mask(survival)) {
+            (Some(birth), Some(survival)) => Ok(Rule { birth, survival }),
+            _ => Err(LifeError::InvalidRule(format!("B{:?}/S{:?}", birth, survival))),
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Rule {
+        Rule::CONWAY
+    }
+}
+
+/// Parses rules in B/S notation such as `B3/S23` or `b36/s23`. Counts past
+/// 8, for wider neighbourhoods, need a comma-separated list instead of
+/// single digits: `B3,12/S2,24`, or `S24,` for a lone count.
+impl FromStr for Rule {
+    type Err = LifeError;
+
+    fn from_str(text: &str) -> Result<Rule, LifeError> {
+        let invalid = || LifeError::InvalidRule(text.to_string());
+
+        let mut parts = text.trim().split('/');
+        let (birth, survival) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(birth), Some(survival), None) => (birth, survival),
+            _ => return Err(invalid()),
+        };
+
+        let counts = |part: &str, prefix: char| -> Result<u32, LifeError> {
+            let mut chars = part.chars();
+            if !chars.next().is_some_and(|c| c.eq_ignore_ascii_case(&prefix)) {
+                return Err(invalid());
+            }
+            let counts = chars.as_str();
+            if counts.contains(',') {
+                return counts
+                    .split(',')
+                    .filter(|count| !count.is_empty())
+                    .try_fold(0, |mask, count| match count.parse::<u32>() {
+                        Ok(n) if n < 32 => Ok(mask | 1 << n),
+                        _ => Err(invalid()),
+                    });
+            }
+            chars.try_fold(0, |mask, c| match c.to_digit(10) {
+                Some(n) if n <= 8 => Ok(mask | 1 << n),
+                _ => Err(invalid()),
+            })
+        };
+
+        Ok(Rule {
+            birth: counts(birth, 'B')?,
+            survival: counts(survival, 'S')?,
+        })
+    }
+}
+
+/// Formats the rule in canonical B/S notation, e.g. `B36/S23`, switching
+/// to the comma form for a part with counts past 8.
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "B")?;
+        write_counts(f, self.birth)?;
+        write!(f, "/S")?;
+        write_counts(f, self.survival)
+    }
+}
+
+fn write_counts(f: &mut fmt::Formatter, mask: u32) -> fmt::Result {
+    let counts: Vec<String> = (0..32).filter(|n| mask & (1 << n) != 0).map(|n| n.to_string()).collect();
+    if mask >> 9 == 0 {
+        write!(f, "{}", counts.concat())
+    } else if counts.len() == 1 {
+        write!(f, "{},", counts[0])
+    } else {
+        write!(f, "{}", counts.join(","))
+    }
+}
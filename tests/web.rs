@@ -9,7 +9,7 @@ use wasm_bindgen_test::*;
 wasm_bindgen_test_configure!(run_in_browser);
 
 extern crate wasm_game_of_life;
-use wasm_game_of_life::Universe;
+use wasm_game_of_life::{Boundary, Cell, Pattern, RleParser, Universe};
 
 #[cfg(test)]
 pub fn input_spaceship() -> Universe {
@@ -29,6 +29,25 @@ pub fn expected_spaceship() -> Universe {
     universe
 }
 
+#[cfg(test)]
+pub fn input_traffic_light() -> Universe {
+    let mut universe = Universe::new();
+    universe.set_width(15);
+    universe.set_height(15);
+    universe.set_cells(&[(6,6), (6,7), (6,8), (7,7)]);
+    universe
+}
+
+#[cfg(test)]
+pub fn expected_traffic_light() -> Universe {
+    let mut universe = Universe::new();
+    universe.set_width(15);
+    universe.set_height(15);
+    universe.set_cells(&[(2,7), (3,7), (4,7), (6,3), (6,4), (6,5),
+                         (6,9), (6,10), (6,11), (8,7), (9,7), (10,7)]);
+    universe
+}
+
 #[wasm_bindgen_test]
 pub fn test_tick() {
     // Let's create a smaller Universe with a small spaceship to test!
@@ -42,3 +61,658 @@ pub fn test_tick() {
     input_universe.tick(0);
     assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
 }
+
+#[wasm_bindgen_test]
+pub fn test_coordinates_wrap_by_modulo() {
+    // Coordinates any number of board lengths past the bottom or right
+    // edge land on the same cell as they would going round the torus.
+    let mut universe = Universe::new();
+    universe.set_width(5);
+    universe.set_height(4);
+    universe.set_cells(&[(1,2)]);
+
+    assert_eq!(universe.get_cell(1 + 3 * 4, 2 + 2 * 5), Cell::Alive);
+    assert_eq!(universe.get_cell(4, 0), universe.get_cell(0, 0));
+    assert_eq!(universe.get_cell(5, 7), Cell::Alive);
+
+    universe.toggle_cell(9, 12);
+    assert_eq!(universe.get_cell(1, 2), Cell::Dead);
+}
+
+#[wasm_bindgen_test]
+pub fn test_and_gate() {
+    let mut universe = Universe::new();
+    universe.setup_and_gate();
+
+    // Let the streams reach the collision points and the eater.
+    for _ in 0..900 {
+        universe.tick(0);
+    }
+    let settled = universe.get_cells().to_vec();
+
+    // From here on the gate repeats every 30 generations, one gun period.
+    for _ in 0..30 {
+        universe.tick(0);
+    }
+    assert_eq!(universe.get_cells(), &settled[..]);
+
+    let population = settled.iter().filter(|&&cell| cell == Cell::Alive).count();
+    assert_eq!(population, 200);
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_reads_frozen_generation() {
+    // A T-tetromino only settles into a symmetric traffic light if each
+    // generation is computed entirely from the previous one; reading a
+    // neighbour that was already updated in the same tick skews it.
+    let mut input_universe = input_traffic_light();
+    let expected_universe = expected_traffic_light();
+
+    for _ in 0..10 {
+        input_universe.tick(0);
+    }
+    assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
+
+    // The traffic light then keeps blinking with period 2.
+    input_universe.tick(0);
+    assert_ne!(&input_universe.get_cells(), &expected_universe.get_cells());
+    input_universe.tick(0);
+    assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
+}
+
+#[cfg(test)]
+pub fn glider() -> Pattern {
+    Pattern::new(3, &[0, 1, 0,
+                      0, 0, 1,
+                      1, 1, 1])
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_pattern_mirrored() {
+    // Unmirrored, the glider heads south-east; flipped left to right it
+    // must head south-west instead, one cell each way every 4 generations.
+    let mut input_universe = Universe::new();
+    input_universe.set_width(12);
+    input_universe.set_height(12);
+    input_universe.insert_pattern(&glider(), 2, 6, 0, true, false);
+
+    let mut expected_universe = Universe::new();
+    expected_universe.set_width(12);
+    expected_universe.set_height(12);
+    expected_universe.insert_pattern(&glider(), 3, 5, 0, true, false);
+
+    for _ in 0..4 {
+        input_universe.tick(0);
+    }
+    assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
+
+    // A quarter turn clockwise on top of the flip sends it north-west.
+    let mut turned_universe = Universe::new();
+    turned_universe.set_width(12);
+    turned_universe.set_height(12);
+    turned_universe.insert_pattern(&glider(), 6, 6, 1, true, false);
+
+    let mut expected_universe = Universe::new();
+    expected_universe.set_width(12);
+    expected_universe.set_height(12);
+    expected_universe.insert_pattern(&glider(), 5, 5, 1, true, false);
+
+    for _ in 0..4 {
+        turned_universe.tick(0);
+    }
+    assert_eq!(&turned_universe.get_cells(), &expected_universe.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_neighbor_weights_drift() {
+    // Only the western neighbour counts, and it counts for 3: a live cell
+    // gives birth to its eastern neighbour and then dies of loneliness, so
+    // it drifts one cell east every generation.
+    let mut input_universe = Universe::new();
+    input_universe.set_width(8);
+    input_universe.set_height(8);
+    input_universe.set_neighbor_weights([0, 0, 0, 3, 0, 0, 0, 0]);
+    input_universe.set_cells(&[(3,2)]);
+
+    let mut expected_universe = Universe::new();
+    expected_universe.set_width(8);
+    expected_universe.set_height(8);
+    expected_universe.set_cells(&[(3,5)]);
+
+    for _ in 0..3 {
+        input_universe.tick(0);
+    }
+    assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_rle_parser_split_chunks() {
+    let rle = "#C split anywhere\nx = 12, y = 4, rule = B3/S23\n11bo$o$\nb2o!\n";
+
+    let mut expected_universe = Universe::new();
+    expected_universe.set_width(12);
+    expected_universe.set_height(4);
+    expected_universe.set_cells(&[(0,11), (1,0), (2,1), (2,2)]);
+
+    for split in 0..=rle.len() {
+        let mut parser = RleParser::new();
+        parser.feed(&rle[..split]);
+        parser.feed(&rle[split..]);
+        let universe = parser.finish().unwrap();
+        assert_eq!(&universe.get_cells(), &expected_universe.get_cells());
+    }
+
+    let mut truncated = RleParser::new();
+    truncated.feed(&rle[..rle.len() - 3]);
+    assert!(truncated.finish().is_err());
+
+    let mut oversized = RleParser::new();
+    oversized.feed("x = 70000, y = 70000\n!");
+    assert!(oversized.finish().is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_despeckle() {
+    // The two singletons go. The pair keeps each other, and (9,9) stays
+    // because the torus wraps it round to touch the block at (0,0).
+    let mut input_universe = Universe::new();
+    input_universe.set_width(10);
+    input_universe.set_height(10);
+    input_universe.set_cells(&[(2,7), (5,5), (0,0), (0,1), (1,0), (1,1), (9,9), (6,1), (7,1)]);
+
+    let mut expected_universe = Universe::new();
+    expected_universe.set_width(10);
+    expected_universe.set_height(10);
+    expected_universe.set_cells(&[(0,0), (0,1), (1,0), (1,1), (9,9), (6,1), (7,1)]);
+
+    input_universe.despeckle();
+    assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
+    assert_eq!(input_universe.generation(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_radius_two_birth_survival() {
+    // A full 5x5 block: only its centre sees all 24 cells of its radius 2
+    // neighbourhood alive, so under S24 it is the one cell that survives.
+    let mut input_universe = Universe::new();
+    input_universe.set_width(11);
+    input_universe.set_height(11);
+    input_universe.set_neighborhood_radius(2).unwrap();
+    input_universe.set_rule_counts(&[], &[24]).unwrap();
+    let block: Vec<(u32, u32)> = (3..8).flat_map(|row| (3..8).map(move |col| (row, col))).collect();
+    input_universe.set_cells(&block);
+
+    let mut expected_universe = Universe::new();
+    expected_universe.set_width(11);
+    expected_universe.set_height(11);
+    expected_universe.set_cells(&[(5,5)]);
+
+    input_universe.tick(0);
+    assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
+
+    // Under B1 that lone cell then gives birth to all 24 cells around it,
+    // and dies itself for lack of a matching survival count.
+    input_universe.set_rule("B1/S24,").unwrap();
+    input_universe.tick(0);
+
+    let mut expected_universe = Universe::new();
+    expected_universe.set_width(11);
+    expected_universe.set_height(11);
+    let ring: Vec<(u32, u32)> = block.iter().cloned().filter(|&cell| cell != (5,5)).collect();
+    expected_universe.set_cells(&ring);
+    assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
+
+    assert!(input_universe.set_neighborhood_radius(3).is_err());
+    assert_eq!(input_universe.neighborhood_radius(), 2);
+}
+
+#[wasm_bindgen_test]
+pub fn test_neighbor_count_overflow() {
+    // Weights big enough to overflow an i32 sum saturate instead, and a
+    // count that large never matches the rule, so the block dies.
+    let mut input_universe = Universe::new();
+    input_universe.set_width(6);
+    input_universe.set_height(6);
+    input_universe.set_neighbor_weights([i32::MAX; 8]);
+    input_universe.set_cells(&[(2,2), (2,3), (3,2), (3,3)]);
+
+    input_universe.tick(0);
+    assert!(input_universe.get_cells().iter().all(|&cell| cell == Cell::Dead));
+}
+
+#[wasm_bindgen_test]
+pub fn test_stamp_grid_blinkers() {
+    // A 3x3 grid of blinkers, 4 cells apart so neighbouring ones never
+    // interact: 9 blinkers of 3 cells each, in either phase.
+    let mut input_universe = Universe::new();
+    input_universe.set_width(12);
+    input_universe.set_height(12);
+    input_universe.stamp_grid(&[1, 1, 1], 3, 4, 4).unwrap();
+
+    let population = |universe: &Universe| universe.get_cells().iter().filter(|&&cell| cell == Cell::Alive).count();
+    assert_eq!(population(&input_universe), 27);
+    for _ in 0..3 {
+        input_universe.tick(0);
+        assert_eq!(population(&input_universe), 27);
+    }
+
+    assert!(input_universe.stamp_grid(&[1, 1, 1], 3, 0, 4).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_delta_round_trip() {
+    let mut reference_universe = Universe::new();
+    reference_universe.set_width(300);
+    reference_universe.set_height(2);
+    reference_universe.set_cells(&[(0,1), (0,2), (0,3), (1,250)]);
+
+    // The board a few cells away from the reference, including a long
+    // matching run that needs a multi-byte varint.
+    let mut target_universe = reference_universe.clone();
+    target_universe.set_cells(&[(0,0), (1,299)]);
+    target_universe.toggle_cell(0, 2);
+
+    let delta = target_universe.delta_against(&reference_universe).unwrap();
+    let mut rebuilt_universe = reference_universe.clone();
+    rebuilt_universe.apply_delta(&delta).unwrap();
+    assert_eq!(&rebuilt_universe.get_cells(), &target_universe.get_cells());
+
+    let same = reference_universe.delta_against(&reference_universe).unwrap();
+    let mut unchanged_universe = reference_universe.clone();
+    unchanged_universe.apply_delta(&same).unwrap();
+    assert_eq!(&unchanged_universe.get_cells(), &reference_universe.get_cells());
+
+    // A delta cut off inside a varint is rejected without touching the board.
+    let broken = [delta.as_slice(), &[0x80]].concat();
+    assert!(rebuilt_universe.apply_delta(&broken).is_err());
+    assert_eq!(&rebuilt_universe.get_cells(), &target_universe.get_cells());
+
+    let mut smaller_universe = Universe::new();
+    smaller_universe.set_width(10);
+    smaller_universe.set_height(2);
+    assert!(smaller_universe.delta_against(&reference_universe).is_err());
+    assert!(smaller_universe.apply_delta(&delta).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_stator_rotor_blinker() {
+    // A blinker's centre is alive in both phases; the four cells around it
+    // take turns, two at a time.
+    let mut input_universe = Universe::new();
+    input_universe.set_width(5);
+    input_universe.set_height(5);
+    input_universe.set_cells(&[(2,1), (2,2), (2,3)]);
+    let before = input_universe.get_cells().to_vec();
+
+    let (stator, rotor) = input_universe.stator_rotor(2);
+    assert_eq!(stator, vec![12]);
+    assert_eq!(rotor, vec![7, 11, 13, 17]);
+    assert_eq!(input_universe.get_cells(), &before[..]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_budget_restarts_after_edit() {
+    // A blinker with its top rows already worked out when the centre is
+    // knocked out: the generation must come from the edited board alone,
+    // where the two cells left both die.
+    let mut input_universe = Universe::new();
+    input_universe.set_width(8);
+    input_universe.set_height(8);
+    input_universe.set_cells(&[(1,1), (1,2), (1,3)]);
+    assert!(!input_universe.tick_budget(20));
+    input_universe.toggle_cell(1, 2);
+
+    let mut expected_universe = input_universe.clone();
+    expected_universe.tick(0);
+    while !input_universe.tick_budget(20) {}
+    assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
+    assert!(input_universe.get_cells().iter().all(|&cell| cell == Cell::Dead));
+
+    // Switching rules halfway through starts the generation over as well.
+    input_universe.set_cells(&[(4,3), (4,4), (4,5)]);
+    assert!(!input_universe.tick_budget(36));
+    input_universe.set_rule("B2/S").unwrap();
+
+    let mut expected_universe = input_universe.clone();
+    expected_universe.tick(0);
+    while !input_universe.tick_budget(36) {}
+    assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_recording_restarts_on_resize() {
+    let mut universe = Universe::new();
+    universe.set_width(6);
+    universe.set_height(6);
+    universe.set_cells(&[(2,1), (2,2), (2,3)]);
+    universe.start_recording();
+    for _ in 0..4 {
+        universe.tick(0);
+    }
+    assert_eq!(universe.replay().len(), 4);
+
+    // The old deltas are for a board of a different size, so the log
+    // starts over and there's no past to draw motion from yet.
+    universe.set_width(8);
+    universe.set_height(8);
+    assert!(universe.replay().is_empty());
+    assert_eq!(universe.recording_start(), Some(universe.get_cells()));
+    assert!(universe.motion_rgba(3, 1, 1, 2, 3).is_empty());
+
+    // Hand edits aren't ticks either, and restart the log the same way.
+    universe.set_cells(&[(4,3), (4,4), (4,5)]);
+    universe.tick(0);
+    assert_eq!(universe.motion_rgba(1, 1, 1, 2, 3).len(), 8 * 8 * 4);
+    universe.toggle_cell(0, 0);
+    assert!(universe.replay().is_empty());
+    assert!(universe.motion_rgba(1, 1, 1, 2, 3).is_empty());
+}
+
+#[wasm_bindgen_test]
+pub fn test_precompute_and_seek() {
+    let mut universe = Universe::new();
+    universe.set_width(10);
+    universe.set_height(10);
+    universe.insert_pattern(&glider(), 1, 1, 0, false, false);
+    universe.set_snapshot_interval(4).unwrap();
+    let mut reference_universe = universe.clone();
+
+    universe.precompute(10);
+    assert_eq!(universe.generation(), 10);
+
+    // Back to a generation between snapshots, then forward again.
+    assert!(universe.seek(3));
+    assert_eq!(universe.generation(), 3);
+    for _ in 0..3 {
+        reference_universe.tick(0);
+    }
+    assert_eq!(&universe.get_cells(), &reference_universe.get_cells());
+    assert!(universe.seek(9));
+    for _ in 0..6 {
+        reference_universe.tick(0);
+    }
+    assert_eq!(&universe.get_cells(), &reference_universe.get_cells());
+
+    // A glider has 5 cells, so a cap of 4 stalls it where it is.
+    universe.set_max_population(4);
+    assert!(!universe.seek(12));
+    assert_eq!(universe.generation(), 9);
+
+    // The cap changed the run, so the old snapshots are gone and there is
+    // nothing to go back to.
+    universe.clear_max_population();
+    assert!(!universe.seek(2));
+    assert_eq!(universe.generation(), 9);
+
+    // Going back before a scheduled rule switch plays the earlier rule again.
+    let mut universe = Universe::new();
+    universe.set_width(10);
+    universe.set_height(10);
+    universe.insert_pattern(&glider(), 1, 1, 0, false, false);
+    universe.set_snapshot_interval(4).unwrap();
+    universe.set_rule_schedule(vec![(3, "B1/S".to_string())]).unwrap();
+    let mut reference_universe = universe.clone();
+
+    universe.precompute(6);
+    assert!(universe.seek(2));
+    assert_eq!(universe.rule(), "B3/S23");
+    for _ in 0..2 {
+        reference_universe.tick(0);
+    }
+    assert_eq!(&universe.get_cells(), &reference_universe.get_cells());
+    assert!(universe.seek(5));
+    assert_eq!(universe.rule(), "B1/S");
+    for _ in 0..3 {
+        reference_universe.tick(0);
+    }
+    assert_eq!(&universe.get_cells(), &reference_universe.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_resize_starts_again() {
+    let mut universe = input_spaceship();
+    for _ in 0..5 {
+        universe.tick(0);
+    }
+    assert_eq!(universe.generation(), 5);
+
+    universe.set_width(9);
+    assert_eq!(universe.generation(), 0);
+    assert_eq!(universe.population_csv(), "generation,population\n0,0\n");
+    assert_eq!(universe.generation_exceeding(0, 3), None);
+}
+
+#[wasm_bindgen_test]
+pub fn test_counts_follow_wrap_schedule() {
+    // Until generation 3 the edges are dead, so the two corner cells don't
+    // see each other across them, even though the board is toroidal by
+    // default.
+    let mut universe = Universe::new();
+    universe.set_width(5);
+    universe.set_height(5);
+    universe.set_cells(&[(0,0), (4,4)]);
+    universe.set_wrap_after(3);
+
+    let counts = universe.neighbor_counts();
+    assert_eq!((counts[0], counts[24]), (0, 0));
+    assert_eq!(universe.edge_energy(), 4);
+
+    universe.despeckle();
+    assert!(universe.get_cells().iter().all(|&cell| cell == Cell::Dead));
+}
+
+#[wasm_bindgen_test]
+pub fn test_generation_exceeding_under_cap() {
+    // The T-tetromino grows to 7 cells, which the population history
+    // keeps, and is then swapped by hand for a blinker that a cap of 2
+    // holds back: it never gets past 5, however crowded the board was.
+    let mut universe = input_traffic_light();
+    assert_eq!(universe.generation_exceeding(5, 3), Some(1));
+    universe.tick(0);
+
+    let alive: Vec<usize> = (0..225).filter(|&idx| universe.get_cells()[idx] == Cell::Alive).collect();
+    assert_eq!(alive.len(), 7);
+    for idx in alive {
+        universe.toggle_cell(idx as u32 / 15, idx as u32 % 15);
+    }
+    universe.set_cells(&[(6,6), (6,7), (6,8)]);
+    universe.set_max_population(2);
+    assert_eq!(universe.generation_exceeding(5, 3), None);
+}
+
+#[wasm_bindgen_test]
+pub fn test_detect_cycle_floyd_short_budget() {
+    let mut block_universe = Universe::new();
+    block_universe.set_width(6);
+    block_universe.set_height(6);
+    block_universe.set_cells(&[(2,2), (2,3), (3,2), (3,3)]);
+    assert_eq!(block_universe.detect_cycle_floyd(1), Some((0, 1)));
+    assert_eq!(block_universe.detect_cycle_floyd(0), None);
+
+    let mut blinker_universe = Universe::new();
+    blinker_universe.set_width(6);
+    blinker_universe.set_height(6);
+    blinker_universe.set_cells(&[(2,1), (2,2), (2,3)]);
+    assert_eq!(blinker_universe.detect_cycle_floyd(1), None);
+    assert_eq!(blinker_universe.detect_cycle_floyd(4), Some((0, 2)));
+}
+
+#[wasm_bindgen_test]
+pub fn test_autocorrelation() {
+    // Nothing alive repeats trivially, with or without any cells.
+    let mut universe = Universe::new();
+    universe.set_width(4);
+    universe.set_height(4);
+    assert_eq!(universe.autocorrelation(2), 1.0);
+    universe.set_width(0);
+    assert_eq!(universe.autocorrelation(2), 1.0);
+
+    // Only the live cells count, not the dead space around them.
+    let mut lone_universe = Universe::new();
+    lone_universe.set_width(20);
+    lone_universe.set_height(20);
+    lone_universe.set_cells(&[(10, 10)]);
+    assert_eq!(lone_universe.autocorrelation(1), 0.0);
+
+    // A blinker's two phases share only the middle cell of five.
+    let mut blinker_universe = Universe::new();
+    blinker_universe.set_width(5);
+    blinker_universe.set_height(5);
+    blinker_universe.set_cells(&[(2,1), (2,2), (2,3)]);
+    assert_eq!(blinker_universe.autocorrelation(2), 1.0);
+    assert_eq!(blinker_universe.autocorrelation(1), 0.2);
+}
+
+#[wasm_bindgen_test]
+pub fn test_stamp_text_clips_dead_edges() {
+    // The second "I" starts past the right edge: cut off with dead edges,
+    // wrapped round to the left with toroidal ones.
+    let mut dead_universe = Universe::new();
+    dead_universe.set_width(6);
+    dead_universe.set_height(6);
+    dead_universe.set_boundary(Boundary::Dead);
+    dead_universe.stamp_text("II", 0, 2);
+    let population = dead_universe.get_cells().iter().filter(|&&cell| cell == Cell::Alive).count();
+    assert_eq!(population, 9);
+    assert_eq!(dead_universe.get_cell(0, 0), Cell::Dead);
+
+    let mut toroidal_universe = Universe::new();
+    toroidal_universe.set_width(6);
+    toroidal_universe.set_height(6);
+    toroidal_universe.stamp_text("II", 0, 2);
+    assert_eq!(toroidal_universe.get_cell(0, 0), Cell::Alive);
+
+    // Edges stay dead until a `set_wrap_after` schedule joins them up.
+    let mut scheduled_universe = Universe::new();
+    scheduled_universe.set_width(6);
+    scheduled_universe.set_height(6);
+    scheduled_universe.set_wrap_after(10);
+    assert_eq!(scheduled_universe.boundary(), Boundary::Dead);
+    scheduled_universe.stamp_text("II", 0, 2);
+    assert_eq!(scheduled_universe.get_cell(0, 0), Cell::Dead);
+}
+
+#[wasm_bindgen_test]
+pub fn test_radius_two_objects() {
+    // Two blocks three columns apart. With the radius 2 neighbourhood the
+    // dead cells between them see both, so they make up one object, which
+    // isn't in the catalog; with radius 1 they are two separate blocks.
+    let mut universe = Universe::new();
+    universe.set_width(16);
+    universe.set_height(16);
+    universe.set_rule("B/S3").unwrap();
+    universe.set_cells(&[(6,5), (6,6), (7,5), (7,6), (6,9), (6,10), (7,9), (7,10)]);
+    let mut radius_one = universe.clone();
+
+    universe.set_neighborhood_radius(2).unwrap();
+    assert_eq!(universe.census(0), vec![("unknown".to_string(), 1)]);
+    assert_eq!(radius_one.census(0), vec![("block".to_string(), 2)]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_light_cone_radius() {
+    let mut universe = Universe::new();
+    universe.set_rule("B1/S").unwrap();
+    assert_eq!(universe.light_cone_radius(3), Ok(3));
+    universe.set_neighborhood_radius(2).unwrap();
+    assert_eq!(universe.light_cone_radius(3), Ok(6));
+
+    universe.set_rule("B3/S23").unwrap();
+    assert_eq!(universe.light_cone_radius(3), Ok(0));
+    assert!(universe.light_cone_radius(u32::MAX).is_err());
+    assert!(universe.light_cone_radius(30000).is_err());
+
+    universe.set_rule("B0/S8").unwrap();
+    assert!(universe.light_cone_radius(3).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_configure() {
+    let mut universe = Universe::new();
+    universe
+        .configure(r#"{"width": 8, "height": 6, "rule": "B36/S23", "boundary": "dead", "max_population": 4}"#)
+        .unwrap();
+    assert_eq!((universe.width(), universe.height()), (8, 6));
+    assert_eq!(universe.rule(), "B36/S23");
+    assert_eq!(universe.boundary(), Boundary::Dead);
+
+    // A glider has 5 cells, over the cap until `null` clears it.
+    universe.insert_pattern(&glider(), 1, 1, 0, false, false);
+    universe.tick(0);
+    assert!(universe.population_cap_hit());
+    assert_eq!(universe.generation(), 0);
+    universe.configure(r#"{"max_population": null}"#).unwrap();
+    universe.tick(0);
+    assert_eq!(universe.generation(), 1);
+
+    // A config with anything wrong in it leaves the board as it was.
+    let before = universe.clone();
+    for json in [
+        r#"{"width": 5, "colour": "red"}"#,
+        r#"{"width": 5, "density": 2}"#,
+        r#"{"width": 100000, "height": 100000}"#,
+        r#"{"width": 1000000000}"#,
+        r#"{"height": 5, "rule": "B3\x/S23"}"#,
+    ] {
+        assert!(universe.configure(json).is_err(), "{}", json);
+        assert_eq!((universe.width(), universe.height()), (8, 6));
+        assert_eq!(universe.rule(), "B36/S23");
+        assert_eq!(universe.get_cells(), before.get_cells());
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_loaded_boards_restart_differently() {
+    let mut first = Universe::from_rle("x = 8, y = 8\n!").unwrap();
+    let mut second = Universe::from_rle("x = 8, y = 8\n!").unwrap();
+    first.restart();
+    second.restart();
+    assert_ne!(first.get_cells(), second.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_dirty_rect() {
+    let mut universe = Universe::new();
+    universe.set_width(8);
+    universe.set_height(8);
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    universe.tick(0);
+    assert_eq!(universe.dirty_rect(), Some(vec![1, 1, 3, 3]));
+
+    // A block never changes.
+    universe.set_width(8);
+    universe.set_cells(&[(0, 0), (0, 1), (1, 0), (1, 1)]);
+    universe.tick(0);
+    assert_eq!(universe.dirty_rect(), None);
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_and_recenter() {
+    // A blinker in the corner is moved to the middle of the board.
+    let mut universe = Universe::new();
+    universe.set_width(8);
+    universe.set_height(8);
+    universe.set_cells(&[(1, 0), (1, 1), (1, 2)]);
+    assert_eq!(universe.tick_and_recenter(), vec![3, 3]);
+    assert_eq!(universe.get_cell(3, 4), Cell::Alive);
+    assert_eq!(universe.get_cell(4, 4), Cell::Alive);
+    assert_eq!(universe.get_cell(5, 4), Cell::Alive);
+
+    universe.set_width(8);
+    assert_eq!(universe.tick_and_recenter(), vec![0, 0]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_population_csv_starts_at_zero() {
+    let mut universe = Universe::new();
+    universe.set_width(6);
+    universe.set_height(6);
+    universe.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]);
+    universe.tick(0);
+    assert_eq!(universe.population_csv(), "generation,population\n0,4\n1,4\n");
+
+    // A board loaded cell by cell starts from its first tick's board.
+    let mut loaded = Universe::from_rle("x = 4, y = 4\n$b2o$b2o!").unwrap();
+    loaded.tick(0);
+    assert_eq!(loaded.population_csv(), "generation,population\n0,4\n1,4\n");
+}